@@ -0,0 +1,177 @@
+use crate::structs::WebDriverError;
+use crate::traits::HttpExecutor;
+use crate::traits::Session;
+
+use log::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+pub const NO_SUCH_ALERT: &str = "no such alert";
+pub const UNEXPECTED_ALERT_OPEN: &str = "unexpected alert open";
+
+/// How often `Alert::wait` looks for a prompt.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// User prompt open in a session. Accepting or dismissing it consumes the handle.
+pub struct Alert<'a, H: HttpExecutor> {
+    session: &'a dyn Session<H>,
+    text: String,
+}
+
+impl<'a, H: HttpExecutor> Alert<'a, H> {
+    /// The prompt open right now, failing with `no such alert` if there is none.
+    pub fn open(session: &'a dyn Session<H>) -> Result<Self, WebDriverError> {
+        let text = session.get_alert_text()?;
+        Ok(Alert { session, text })
+    }
+
+    /// Polls for a prompt until `timeout`, then fails with `no such alert`.
+    pub fn wait(session: &'a dyn Session<H>, timeout: Duration) -> Result<Self, WebDriverError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            match Alert::open(session) {
+                Err(ref e) if e.error == NO_SUCH_ALERT && Instant::now() + POLL_INTERVAL <= deadline =>
+                    thread::sleep(POLL_INTERVAL),
+                Err(ref e) if e.error == NO_SUCH_ALERT => return Err(WebDriverError::new(
+                    NO_SUCH_ALERT,
+                    &format!("No alert opened within {:?}", timeout),
+                )),
+                result => return result,
+            }
+        }
+    }
+
+    /// Message of the prompt, as read when the handle was obtained.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Types into a `prompt()`; fails for alerts and confirms.
+    pub fn send_keys(&self, text: &str) -> Result<(), WebDriverError> {
+        self.session.set_alert_text(text)
+    }
+
+    pub fn accept(self) -> Result<(), WebDriverError> {
+        self.session.accept_alert()
+    }
+
+    pub fn dismiss(self) -> Result<(), WebDriverError> {
+        self.session.dismiss_alert()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlertAction {
+    Accept,
+    Dismiss,
+}
+
+impl AlertAction {
+    fn command(&self) -> &'static str {
+        match self {
+            AlertAction::Accept => "accept",
+            AlertAction::Dismiss => "dismiss",
+        }
+    }
+}
+
+/// What to do with prompts which make commands fail with `unexpected alert open`.
+#[derive(Clone, Debug)]
+pub struct AlertPolicy {
+    pub action: AlertAction,
+    pub max_retries: u32,
+}
+
+impl AlertPolicy {
+    pub fn accept() -> Self {
+        AlertPolicy { action: AlertAction::Accept, max_retries: 3 }
+    }
+
+    pub fn dismiss() -> Self {
+        AlertPolicy { action: AlertAction::Dismiss, max_retries: 3 }
+    }
+
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+}
+
+/// Decorator handling unexpected prompts by the policy and retrying the command they blocked.
+///
+/// Drivers check for prompts before running a command, so the retry does not repeat its effect.
+/// The driver's own `unhandledPromptBehavior` may already have closed the prompt, in which case
+/// only the retry is done.
+#[derive(Clone)]
+pub struct AlertExecutor<H: HttpExecutor> {
+    policy: AlertPolicy,
+    inner: Box<H>,
+}
+
+impl<H: HttpExecutor> AlertExecutor<H> {
+    pub fn new(inner: Box<H>, policy: AlertPolicy) -> Self {
+        AlertExecutor { policy, inner }
+    }
+
+    pub fn into_inner(self) -> Box<H> {
+        self.inner
+    }
+
+    fn with_alerts<T, F>(&self, path: &str, command: F) -> Result<T, WebDriverError>
+        where F: Fn(&H) -> Result<T, WebDriverError>
+    {
+        let mut attempt = 0;
+        loop {
+            match command(&self.inner) {
+                Err(e) if e.error == UNEXPECTED_ALERT_OPEN && attempt < self.policy.max_retries => {
+                    let session = session_path(path).ok_or_else(|| e.clone())?;
+                    attempt += 1;
+                    warn!("Handling unexpected alert {:?} with {:?} before retrying {}",
+                          e.alert_text().unwrap_or_default(), self.policy.action, path);
+                    let handle = format!("{}/alert/{}", session, self.policy.action.command());
+                    match self.inner.post::<Value, _>(&handle, Value::Object(Map::new())) {
+                        Err(ref closed) if closed.error == NO_SUCH_ALERT => {}
+                        Err(failed) => return Err(failed),
+                        Ok(_) => {}
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl<H: HttpExecutor> HttpExecutor for AlertExecutor<H> {
+    fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, WebDriverError> {
+        self.with_alerts(path, |http| http.get(path))
+    }
+
+    fn post<T: DeserializeOwned, S: Serialize>(&self, path: &str, body: S) -> Result<T, WebDriverError> {
+        let body = serde_json::to_value(body)
+            .map_err(|e| WebDriverError::new("invalid argument", &e.to_string()))?;
+        self.with_alerts(path, |http| http.post(path, &body))
+    }
+
+    fn delete<T: DeserializeOwned>(&self, path: &str) -> Result<T, WebDriverError> {
+        self.with_alerts(path, |http| http.delete(path))
+    }
+
+    fn is_local(&self) -> bool {
+        self.inner.is_local()
+    }
+}
+
+/// `session/{id}` prefix of a command path.
+fn session_path(path: &str) -> Option<String> {
+    let mut segments = path.trim_start_matches('/').split('/');
+    match (segments.next(), segments.next()) {
+        (Some("session"), Some(id)) if !id.is_empty() => Some(format!("session/{}", id)),
+        _ => None,
+    }
+}
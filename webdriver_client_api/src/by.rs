@@ -0,0 +1,142 @@
+use crate::structs::WebDriverLocator;
+use crate::traits::LocatorStrategy;
+
+use std::fmt::Display;
+use std::fmt::Error;
+use std::fmt::Formatter;
+use std::fmt::Write;
+
+pub const CSS_SELECTOR: &str = "css selector";
+pub const LINK_TEXT: &str = "link text";
+pub const PARTIAL_LINK_TEXT: &str = "partial link text";
+pub const TAG_NAME: &str = "tag name";
+pub const XPATH: &str = "xpath";
+
+/// A locator strategy together with its value, e.g. `By::css("form > input")`.
+///
+/// Strategies WebDriver does not know natively (`id`, `name`, class names, test ids)
+/// are translated to CSS selectors the way the W3C specification suggests.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct By {
+    using: String,
+    value: String,
+}
+
+impl By {
+    /// Locator of any strategy, including vendor specific ones such as Appium's `accessibility id`.
+    pub fn custom(using: &str, value: &str) -> Self {
+        By { using: using.to_string(), value: value.to_string() }
+    }
+
+    pub fn new(strategy: &dyn LocatorStrategy, value: &str) -> Self {
+        By::custom(strategy.get_using_str(), value)
+    }
+
+    pub fn css(selector: &str) -> Self {
+        By::custom(CSS_SELECTOR, selector)
+    }
+
+    pub fn xpath(xpath: &str) -> Self {
+        By::custom(XPATH, xpath)
+    }
+
+    pub fn link_text(text: &str) -> Self {
+        By::custom(LINK_TEXT, text)
+    }
+
+    pub fn partial_link_text(text: &str) -> Self {
+        By::custom(PARTIAL_LINK_TEXT, text)
+    }
+
+    pub fn tag_name(name: &str) -> Self {
+        By::custom(TAG_NAME, name)
+    }
+
+    pub fn id(id: &str) -> Self {
+        By::css(&format!("#{}", escape_identifier(id)))
+    }
+
+    pub fn class_name(class: &str) -> Self {
+        By::css(&format!(".{}", escape_identifier(class)))
+    }
+
+    pub fn name(name: &str) -> Self {
+        By::attribute("name", name)
+    }
+
+    /// Element whose `data-testid` attribute equals `id`.
+    pub fn test_id(id: &str) -> Self {
+        By::attribute("data-testid", id)
+    }
+
+    /// Element whose attribute `name` equals `value`.
+    pub fn attribute(name: &str, value: &str) -> Self {
+        By::css(&format!("*[{}={}]", escape_identifier(name), quote(value)))
+    }
+
+    pub fn using(&self) -> &str {
+        self.using.as_str()
+    }
+
+    pub fn value(&self) -> &str {
+        self.value.as_str()
+    }
+}
+
+impl LocatorStrategy for By {
+    fn get_using_str(&self) -> &str {
+        self.using()
+    }
+}
+
+impl From<(WebDriverLocator, &str)> for By {
+    fn from((strategy, value): (WebDriverLocator, &str)) -> Self {
+        By::new(&strategy, value)
+    }
+}
+
+impl Display for By {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(f, "{} {}", self.using, quote(&self.value))
+    }
+}
+
+/// Escapes `ident` for use as a CSS identifier, following CSSOM `CSS.escape()`.
+pub fn escape_identifier(ident: &str) -> String {
+    let mut escaped = String::with_capacity(ident.len());
+    let chars: Vec<char> = ident.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        let leading_digit = c.is_ascii_digit() && (i == 0 || (i == 1 && chars[0] == '-'));
+        match c {
+            '\0' => escaped.push('\u{FFFD}'),
+            '\u{1}'..='\u{1F}' | '\u{7F}' => write!(escaped, "\\{:x} ", c as u32).unwrap(),
+            _ if leading_digit => write!(escaped, "\\{:x} ", c as u32).unwrap(),
+            '-' if chars.len() == 1 => escaped.push_str("\\-"),
+            _ if c >= '\u{80}' || c == '-' || c == '_' || c.is_ascii_alphanumeric() => escaped.push(c),
+            _ => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+        }
+    }
+    escaped
+}
+
+/// Double quoted CSS string.
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\0' => quoted.push('\u{FFFD}'),
+            '\u{1}'..='\u{1F}' | '\u{7F}' => write!(quoted, "\\{:x} ", c as u32).unwrap(),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,248 @@
+use crate::structs::WebDriverError;
+use crate::traits::HttpExecutor;
+
+use log::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::json;
+use serde_json::Value;
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+/// One recorded request with the response the remote end gave to it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Interaction {
+    pub method: String,
+    pub path: String,
+    #[serde(default)]
+    pub body: Option<Value>,
+    pub response: Result<Value, WebDriverError>,
+}
+
+/// On-disk cassette layout. Plain arrays of interactions from older recordings are still read.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Cassette {
+    Header {
+        #[serde(default)]
+        is_local: bool,
+        interactions: Vec<Interaction>,
+    },
+    Plain(Vec<Interaction>),
+}
+
+impl Interaction {
+    fn matches(&self, method: &str, path: &str, body: &Option<Value>) -> bool {
+        self.method == method && self.path == path && &self.body == body
+    }
+}
+
+/// Decorator recording every request passing through it, with its response, to a cassette file.
+/// The file is written by `save` and once the last clone of the executor is dropped.
+#[derive(Clone)]
+pub struct RecordingExecutor<H: HttpExecutor> {
+    inner: Box<H>,
+    tape: Arc<Tape>,
+}
+
+struct Tape {
+    file: PathBuf,
+    /// Whether the recorded remote end shared the local file system, see `HttpExecutor::is_local`.
+    is_local: bool,
+    /// Interactions so far, and whether some of them are not written yet.
+    interactions: Mutex<(Vec<Interaction>, bool)>,
+}
+
+impl Tape {
+    fn save(&self) -> io::Result<()> {
+        let mut interactions = self.interactions.lock().unwrap_or_else(|e| e.into_inner());
+        let cassette = json!({"is_local": self.is_local, "interactions": &interactions.0});
+        let json = serde_json::to_string_pretty(&cassette)?;
+        fs::write(&self.file, json)?;
+        interactions.1 = false;
+        Ok(())
+    }
+}
+
+impl Drop for Tape {
+    fn drop(&mut self) {
+        let unsaved = self.interactions.get_mut().map(|i| i.1).unwrap_or(true);
+        if unsaved {
+            if let Err(e) = self.save() {
+                error!("Cannot write cassette {}: {}", self.file.display(), e);
+            }
+        }
+    }
+}
+
+impl<H: HttpExecutor> RecordingExecutor<H> {
+    pub fn new(inner: Box<H>, file: &Path) -> Self {
+        let is_local = inner.is_local();
+        RecordingExecutor {
+            inner,
+            tape: Arc::new(Tape {
+                file: file.to_path_buf(),
+                is_local,
+                interactions: Mutex::new((Vec::new(), false)),
+            }),
+        }
+    }
+
+    pub fn into_inner(self) -> Box<H> {
+        self.inner
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        self.tape.save()
+    }
+
+    fn record<T: DeserializeOwned>(&self,
+                                   method: &str,
+                                   path: &str,
+                                   body: Option<Value>,
+                                   response: Result<Value, WebDriverError>) -> Result<T, WebDriverError> {
+        let mut interactions = self.tape.interactions.lock().unwrap_or_else(|e| e.into_inner());
+        interactions.0.push(Interaction {
+            method: method.to_string(),
+            path: path.to_string(),
+            body,
+            response: response.clone(),
+        });
+        interactions.1 = true;
+        drop(interactions);
+        decode(response)
+    }
+}
+
+impl<H: HttpExecutor> HttpExecutor for RecordingExecutor<H> {
+    fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, WebDriverError> {
+        self.record("GET", path, None, self.inner.get(path))
+    }
+
+    fn post<T: DeserializeOwned, S: Serialize>(&self, path: &str, body: S) -> Result<T, WebDriverError> {
+        let body = to_value(body)?;
+        let response = self.inner.post(path, &body);
+        self.record("POST", path, Some(body), response)
+    }
+
+    fn delete<T: DeserializeOwned>(&self, path: &str) -> Result<T, WebDriverError> {
+        self.record("DELETE", path, None, self.inner.delete(path))
+    }
+
+    fn is_local(&self) -> bool {
+        self.inner.is_local()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplayMode {
+    /// Requests must arrive in the recorded order.
+    InOrder,
+    /// Each request is served by the first unused interaction with the same method, path and body.
+    Match,
+}
+
+/// Executor serving responses from a cassette, panicking on requests it cannot match.
+#[derive(Clone)]
+pub struct ReplayExecutor {
+    mode: ReplayMode,
+    is_local: bool,
+    interactions: Arc<Mutex<Vec<Option<Interaction>>>>,
+}
+
+impl ReplayExecutor {
+    pub fn new(interactions: Vec<Interaction>, mode: ReplayMode) -> Self {
+        ReplayExecutor {
+            mode,
+            is_local: false,
+            interactions: Arc::new(Mutex::new(interactions.into_iter().map(Some).collect())),
+        }
+    }
+
+    pub fn from_file(file: &Path, mode: ReplayMode) -> io::Result<Self> {
+        match serde_json::from_str(&fs::read_to_string(file)?)? {
+            Cassette::Header { is_local, interactions } => {
+                Ok(ReplayExecutor::new(interactions, mode).local(is_local))
+            }
+            Cassette::Plain(interactions) => Ok(ReplayExecutor::new(interactions, mode)),
+        }
+    }
+
+    /// Answers `is_local` like the recorded remote end, so e.g. uploads take the same path.
+    pub fn local(mut self, is_local: bool) -> Self {
+        self.is_local = is_local;
+        self
+    }
+
+    /// Number of recorded interactions not served yet.
+    pub fn remaining(&self) -> usize {
+        self.interactions.lock().unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|i| i.is_some())
+            .count()
+    }
+
+    fn replay<T: DeserializeOwned>(&self, method: &str, path: &str, body: Option<Value>) -> Result<T, WebDriverError> {
+        let mut interactions = self.interactions.lock().unwrap_or_else(|e| e.into_inner());
+        let slot = match self.mode {
+            ReplayMode::InOrder => interactions.iter_mut()
+                .find(|i| i.is_some())
+                .filter(|i| i.as_ref().is_some_and(|i| i.matches(method, path, &body))),
+            ReplayMode::Match => interactions.iter_mut()
+                .find(|i| i.as_ref().is_some_and(|i| i.matches(method, path, &body))),
+        };
+        match slot.and_then(Option::take) {
+            Some(interaction) => {
+                debug!("Replaying {} {}", method, path);
+                decode(interaction.response)
+            }
+            None => {
+                drop(interactions);
+                panic!("Unmatched request in cassette ({:?} mode): {} {} {}",
+                       self.mode,
+                       method,
+                       path,
+                       body.map(|b| b.to_string()).unwrap_or_default())
+            }
+        }
+    }
+}
+
+impl HttpExecutor for ReplayExecutor {
+    fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, WebDriverError> {
+        self.replay("GET", path, None)
+    }
+
+    fn post<T: DeserializeOwned, S: Serialize>(&self, path: &str, body: S) -> Result<T, WebDriverError> {
+        self.replay("POST", path, Some(to_value(body)?))
+    }
+
+    fn delete<T: DeserializeOwned>(&self, path: &str) -> Result<T, WebDriverError> {
+        self.replay("DELETE", path, None)
+    }
+
+    fn is_local(&self) -> bool {
+        self.is_local
+    }
+}
+
+fn to_value<S: Serialize>(body: S) -> Result<Value, WebDriverError> {
+    serde_json::to_value(body).map_err(|e| WebDriverError::new("invalid argument", &e.to_string()))
+}
+
+/// Reads a raw response value as `T`, falling back to a `WebDriverError` like the HTTP backend does.
+pub fn decode<T: DeserializeOwned>(response: Result<Value, WebDriverError>) -> Result<T, WebDriverError> {
+    let value = response?;
+    match serde_json::from_value::<T>(value.clone()) {
+        Ok(v) => Ok(v),
+        Err(e) => match serde_json::from_value::<WebDriverError>(value) {
+            Ok(error) => Err(error),
+            Err(_) => Err(WebDriverError::new("unknown error", &format!("Cannot read response: {}", e))),
+        }
+    }
+}
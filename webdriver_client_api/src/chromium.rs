@@ -0,0 +1,101 @@
+use crate::structs::WebDriverError;
+use crate::traits::HttpExecutor;
+use crate::traits::Session;
+
+use serde_json::Map;
+use serde_json::Value;
+
+/// Network throttling as understood by chromedriver; throughputs are in bytes per second.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NetworkConditions {
+    #[serde(default)]
+    pub offline: bool,
+    /// Additional latency in milliseconds.
+    pub latency: u64,
+    pub download_throughput: u64,
+    pub upload_throughput: u64,
+}
+
+/// Vendor prefix of a chromium based browser, `goog` for Chrome and `ms` for Edge,
+/// or `None` if `browser_name` is not known to be chromium based.
+pub fn browser_vendor_prefix(browser_name: &str) -> Option<&'static str> {
+    match browser_name {
+        "chrome" | "chromium" => Some("goog"),
+        "MicrosoftEdge" | "msedge" => Some("ms"),
+        _ => None,
+    }
+}
+
+/// Vendor commands of chromium based drivers (chromedriver, msedgedriver).
+pub trait ChromiumSession<H: HttpExecutor>: Session<H> {
+    /// Prefix of vendor specific endpoints, `goog` for Chrome and `ms` for Edge.
+    fn vendor_prefix(&self) -> &str {
+        self.get_capabilities().get("browserName")
+            .and_then(Value::as_str)
+            .and_then(browser_vendor_prefix)
+            .unwrap_or("goog")
+    }
+
+    /// Runs a Chrome DevTools Protocol command, returning its result.
+    fn execute_cdp(&self, method: &str, params: Value) -> Result<Value, WebDriverError>;
+
+    fn get_network_conditions(&self) -> Result<NetworkConditions, WebDriverError>;
+    fn set_network_conditions(&self, conditions: &NetworkConditions) -> Result<(), WebDriverError>;
+    fn delete_network_conditions(&self) -> Result<(), WebDriverError>;
+
+    fn get_cast_sinks(&self) -> Result<Vec<Value>, WebDriverError>;
+    fn set_cast_sink_to_use(&self, sink_name: &str) -> Result<(), WebDriverError>;
+    fn start_tab_mirroring(&self, sink_name: &str) -> Result<(), WebDriverError>;
+    fn start_desktop_mirroring(&self, sink_name: &str) -> Result<(), WebDriverError>;
+    fn stop_casting(&self, sink_name: &str) -> Result<(), WebDriverError>;
+    fn get_cast_issue_message(&self) -> Result<String, WebDriverError>;
+
+    /// Launches the Chrome app with the given id.
+    fn launch_app(&self, id: &str) -> Result<(), WebDriverError>;
+
+    fn set_geolocation_override(&self, latitude: f64, longitude: f64, accuracy: f64) -> Result<(), WebDriverError> {
+        let mut params = Map::new();
+        params.insert("latitude".to_string(), Value::from(latitude));
+        params.insert("longitude".to_string(), Value::from(longitude));
+        params.insert("accuracy".to_string(), Value::from(accuracy));
+        self.execute_cdp("Emulation.setGeolocationOverride", Value::Object(params)).map(|_| ())
+    }
+
+    fn clear_geolocation_override(&self) -> Result<(), WebDriverError> {
+        self.execute_cdp("Emulation.clearGeolocationOverride", Value::Object(Map::new())).map(|_| ())
+    }
+
+    /// Overrides the timezone with an ICU id such as `Europe/Berlin`, or restores it with `""`.
+    fn set_timezone_override(&self, timezone_id: &str) -> Result<(), WebDriverError> {
+        let mut params = Map::new();
+        params.insert("timezoneId".to_string(), Value::from(timezone_id));
+        self.execute_cdp("Emulation.setTimezoneOverride", Value::Object(params)).map(|_| ())
+    }
+
+    /// Throttles the network through DevTools; unlike `set_network_conditions` this also works headless.
+    fn emulate_network_conditions(&self, conditions: &NetworkConditions) -> Result<(), WebDriverError> {
+        let mut params = Map::new();
+        params.insert("offline".to_string(), Value::from(conditions.offline));
+        params.insert("latency".to_string(), Value::from(conditions.latency));
+        params.insert("downloadThroughput".to_string(), Value::from(conditions.download_throughput));
+        params.insert("uploadThroughput".to_string(), Value::from(conditions.upload_throughput));
+        self.execute_cdp("Network.emulateNetworkConditions", Value::Object(params)).map(|_| ())
+    }
+
+    /// Emulates a CSS media type such as `print` and media features such as
+    /// `("prefers-color-scheme", "dark")`; empty values restore the defaults.
+    fn set_emulated_media(&self, media: &str, features: &[(&str, &str)]) -> Result<(), WebDriverError> {
+        let features: Vec<Value> = features.iter()
+            .map(|(name, value)| {
+                let mut feature = Map::new();
+                feature.insert("name".to_string(), Value::from(*name));
+                feature.insert("value".to_string(), Value::from(*value));
+                Value::Object(feature)
+            })
+            .collect();
+        let mut params = Map::new();
+        params.insert("media".to_string(), Value::from(media));
+        params.insert("features".to_string(), Value::from(features));
+        self.execute_cdp("Emulation.setEmulatedMedia", Value::Object(params)).map(|_| ())
+    }
+}
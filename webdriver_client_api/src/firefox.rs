@@ -0,0 +1,59 @@
+use crate::structs::WebDriverError;
+use crate::traits::HttpExecutor;
+use crate::traits::Session;
+
+use log::*;
+
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::path::Path;
+
+/// Whether commands target the web page or the browser chrome itself.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FirefoxContext {
+    Chrome,
+    Content,
+}
+
+/// Vendor commands of geckodriver.
+pub trait FirefoxSession<H: HttpExecutor>: Session<H> {
+    /// Installs a local `.xpi` add-on, returning its id. Temporary add-ons may be unsigned
+    /// and are removed when the browser closes.
+    fn install_addon(&self, path: &Path, temporary: bool) -> Result<String, WebDriverError>;
+    fn uninstall_addon(&self, id: &str) -> Result<(), WebDriverError>;
+    fn get_context(&self) -> Result<FirefoxContext, WebDriverError>;
+    fn set_context(&self, context: FirefoxContext) -> Result<(), WebDriverError>;
+    /// Base64-encoded PNG of the whole document, not only the viewport.
+    fn full_page_screenshot(&self) -> Result<String, WebDriverError>;
+
+    /// Switches to `context` until the returned guard is dropped, then switches back.
+    fn scoped_context(&self, context: FirefoxContext) -> Result<ContextGuard<'_, H, Self>, WebDriverError> {
+        let previous = self.get_context()?;
+        self.set_context(context)?;
+        Ok(ContextGuard { session: self, previous, http: PhantomData })
+    }
+}
+
+/// Restores the previous Firefox context when dropped.
+pub struct ContextGuard<'a, H: HttpExecutor, S: FirefoxSession<H> + ?Sized> {
+    session: &'a S,
+    previous: FirefoxContext,
+    http: PhantomData<H>,
+}
+
+impl<'a, H: HttpExecutor, S: FirefoxSession<H> + ?Sized> Deref for ContextGuard<'a, H, S> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        self.session
+    }
+}
+
+impl<'a, H: HttpExecutor, S: FirefoxSession<H> + ?Sized> Drop for ContextGuard<'a, H, S> {
+    fn drop(&mut self) {
+        if let Err(e) = self.session.set_context(self.previous) {
+            error!("Cannot restore Firefox context {:?}: {}", self.previous, e);
+        }
+    }
+}
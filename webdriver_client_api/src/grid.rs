@@ -0,0 +1,122 @@
+use crate::structs::Capabilities;
+use crate::structs::WebDriverError;
+
+use serde_json::Value;
+
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+/// Status payload of a Selenium 4 grid, a superset of the plain `Status`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GridStatus {
+    pub ready: bool,
+    pub message: String,
+    #[serde(default)]
+    pub nodes: Vec<GridNode>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GridNode {
+    pub id: String,
+    pub uri: String,
+    /// `UP`, `DRAINING` or `DOWN`.
+    pub availability: String,
+    #[serde(default)]
+    pub max_sessions: u32,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub os_info: Option<Value>,
+    #[serde(default)]
+    pub slots: Vec<GridSlot>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GridSlot {
+    pub id: Value,
+    #[serde(default)]
+    pub last_started: Option<String>,
+    /// Session running in the slot, `None` if the slot is free.
+    #[serde(default)]
+    pub session: Option<Value>,
+    pub stereotype: Capabilities,
+}
+
+impl GridNode {
+    pub fn is_up(&self) -> bool {
+        self.availability.eq_ignore_ascii_case("UP")
+    }
+}
+
+impl GridSlot {
+    pub fn is_free(&self) -> bool {
+        self.session.is_none()
+    }
+
+    /// Whether every requested capability the stereotype declares has the requested value.
+    ///
+    /// `caps` may be a plain capabilities map or a new session body, which matches if `alwaysMatch`
+    /// merged with any of its `firstMatch` entries does.
+    pub fn matches(&self, caps: &Capabilities) -> bool {
+        let capabilities = match caps.get("capabilities").and_then(Value::as_object) {
+            Some(capabilities) => capabilities,
+            None => return self.matches_all(caps.iter()),
+        };
+        let always = capabilities.get("alwaysMatch").and_then(Value::as_object);
+        let first = capabilities.get("firstMatch")
+            .and_then(Value::as_array)
+            .filter(|entries| !entries.is_empty());
+        match first {
+            Some(entries) => entries.iter().any(|entry| {
+                let entry = entry.as_object().into_iter().flatten();
+                self.matches_all(always.into_iter().flatten().chain(entry))
+            }),
+            None => self.matches_all(always.into_iter().flatten()),
+        }
+    }
+
+    fn matches_all<'a>(&self, mut caps: impl Iterator<Item = (&'a String, &'a Value)>) -> bool {
+        caps.all(|(name, wanted)| match self.stereotype.get(name) {
+            Some(Value::String(actual)) if name == "platformName" =>
+                wanted.as_str().is_some_and(|w| w.eq_ignore_ascii_case(actual)),
+            Some(actual) => actual == wanted,
+            None => name != "browserName",
+        })
+    }
+}
+
+impl GridStatus {
+    pub fn free_slots(&self, caps: &Capabilities) -> Vec<&GridSlot> {
+        self.nodes.iter()
+            .filter(|n| n.is_up())
+            .flat_map(|n| n.slots.iter())
+            .filter(|s| s.is_free() && s.matches(caps))
+            .collect()
+    }
+}
+
+/// Remote end which is a Selenium grid hub.
+pub trait Grid {
+    fn grid_status(&self) -> Result<GridStatus, WebDriverError>;
+
+    /// Polls the grid status until a free slot matches `caps`, failing with `timeout` otherwise.
+    fn wait_for_slot(&self, caps: &Capabilities, timeout: Duration, interval: Duration) -> Result<(), WebDriverError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let status = self.grid_status()?;
+            if !status.free_slots(caps).is_empty() {
+                return Ok(());
+            }
+            if Instant::now() + interval > deadline {
+                return Err(WebDriverError::new(
+                    "timeout",
+                    &format!("No free grid slot matching {} within {:?}", Value::Object(caps.clone()), timeout),
+                ));
+            }
+            thread::sleep(interval);
+        }
+    }
+}
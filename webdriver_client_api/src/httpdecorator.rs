@@ -4,6 +4,8 @@ use crate::structs::WebDriverError;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
+use std::sync::Arc;
+
 impl<H: HttpExecutor> HttpExecutor for HttpExecutorPathDecorator<H> {
     fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, WebDriverError> {
         self.inner.get(format!("{}/{}", &self.path, path).trim_end_matches("/"))
@@ -16,22 +18,29 @@ impl<H: HttpExecutor> HttpExecutor for HttpExecutorPathDecorator<H> {
     fn delete<T: DeserializeOwned>(&self, path: &str) -> Result<T, WebDriverError> {
         self.inner.delete(format!("{}/{}", &self.path, path).trim_end_matches("/"))
     }
+
+    fn is_local(&self) -> bool {
+        self.inner.is_local()
+    }
 }
 
 #[derive(Clone)]
 pub struct HttpExecutorPathDecorator<H: HttpExecutor + Clone> {
     path: String,
-    inner: Box<H>,
+    inner: Arc<H>,
 }
 
 impl<H: HttpExecutor> HttpExecutorPathDecorator<H> {
-    pub fn new(facade: Box<H>, path: String) -> HttpExecutorPathDecorator<H> {
+    pub fn new(facade: Arc<H>, path: String) -> HttpExecutorPathDecorator<H> {
         HttpExecutorPathDecorator {
             path,
             inner: facade,
         }
     }
-    pub fn into_inner(self) -> Box<H>{
+    pub fn inner(&self) -> &Arc<H> {
+        &self.inner
+    }
+    pub fn into_inner(self) -> Arc<H>{
         self.inner
     }
 }
@@ -0,0 +1,157 @@
+use crate::structs::WebDriverError;
+use crate::WEB_ELEMENT_IDENTIFIER;
+
+use serde_json::Map;
+use serde_json::Value;
+
+/// Key of element references in JSON Wire responses, where W3C uses `WEB_ELEMENT_IDENTIFIER`.
+pub const ELEMENT_IDENTIFIER: &str = "ELEMENT";
+
+/// W3C error code of a numeric JSON Wire status.
+pub fn error_code(status: u64) -> &'static str {
+    match status {
+        6 => "invalid session id",
+        7 => "no such element",
+        8 => "no such frame",
+        9 => "unknown command",
+        10 => "stale element reference",
+        11 | 60 => "element not interactable",
+        12 | 15 => "invalid element state",
+        17 => "javascript error",
+        19 | 32 => "invalid selector",
+        21 => "timeout",
+        23 => "no such window",
+        24 => "invalid cookie domain",
+        25 => "unable to set cookie",
+        26 => "unexpected alert open",
+        27 => "no such alert",
+        28 => "script timeout",
+        29 | 61 => "invalid argument",
+        30 | 31 => "unsupported operation",
+        33 => "session not created",
+        34 => "move target out of bounds",
+        62 => "no such cookie",
+        63 => "unable to capture screen",
+        64 => "element click intercepted",
+        _ => "unknown error",
+    }
+}
+
+/// Numeric JSON Wire status of a W3C error code, the inverse of `error_code`.
+pub fn status_code(error: &str) -> u64 {
+    match error {
+        "unknown error" => 13,
+        _ => (6..=64).find(|status| error_code(*status) == error).unwrap_or(13),
+    }
+}
+
+/// Adds `desiredCapabilities` next to the W3C `capabilities` of a new session body,
+/// merging `alwaysMatch` with the first `firstMatch` entry.
+pub fn with_desired_capabilities(mut body: Value) -> Value {
+    if let Some(object) = body.as_object_mut() {
+        if !object.contains_key("desiredCapabilities") {
+            let capabilities = object.get("capabilities");
+            let mut desired = capabilities
+                .and_then(|c| c.get("alwaysMatch"))
+                .and_then(Value::as_object)
+                .cloned()
+                .unwrap_or_default();
+            if let Some(first) = capabilities
+                .and_then(|c| c.get("firstMatch"))
+                .and_then(|f| f.get(0))
+                .and_then(Value::as_object) {
+                desired.extend(first.iter().map(|(k, v)| (k.clone(), v.clone())));
+            }
+            object.insert("desiredCapabilities".to_string(), Value::Object(desired));
+        }
+    }
+    body
+}
+
+/// Adds the legacy `ELEMENT` key to every W3C element reference in a request body,
+/// as JSON Wire remote ends only understand that one.
+pub fn with_legacy_element_keys(body: Value) -> Value {
+    match body {
+        Value::Array(items) => Value::Array(items.into_iter().map(with_legacy_element_keys).collect()),
+        Value::Object(object) => {
+            let mut object: Map<String, Value> = object.into_iter()
+                .map(|(k, v)| (k, with_legacy_element_keys(v)))
+                .collect();
+            if let Some(id) = object.get(WEB_ELEMENT_IDENTIFIER).cloned() {
+                object.entry(ELEMENT_IDENTIFIER.to_string()).or_insert(id);
+            }
+            Value::Object(object)
+        }
+        body => body,
+    }
+}
+
+/// Unwraps a `{status, sessionId, value}` response into the W3C `value`.
+///
+/// Returns `None` if there is no `value` at all. Responses without a numeric `status` are
+/// already W3C and returned as is, so mixed grids keep working. New session responses
+/// are rewrapped as `{sessionId, capabilities}`.
+pub fn unwrap_response(mut response: Value, new_session: bool) -> Option<Result<Value, WebDriverError>> {
+    let value = response.get_mut("value").map(Value::take);
+    let status = match response.get("status").and_then(Value::as_u64) {
+        Some(status) => status,
+        None => return value.map(Ok),
+    };
+    let value = value.unwrap_or(Value::Null);
+
+    if status != 0 {
+        return Some(Err(error(status, value)));
+    }
+    if new_session && value.get("sessionId").is_none() {
+        let mut session = Map::new();
+        session.insert("sessionId".to_string(), response.get("sessionId").cloned().unwrap_or(Value::Null));
+        session.insert("capabilities".to_string(), value);
+        return Some(Ok(Value::Object(session)));
+    }
+    Some(Ok(value))
+}
+
+/// Error of a non-zero status, keeping the driver's stack trace and any other `value` members
+/// as `data`, like a W3C error body does.
+fn error(status: u64, value: Value) -> WebDriverError {
+    let mut value = match value {
+        Value::Object(object) => object,
+        _ => Map::new(),
+    };
+    let message = value.remove("message");
+    let stacktrace = value.remove("stacktrace").or_else(|| value.remove("stackTrace"));
+    let mut data = match value.remove("data") {
+        Some(Value::Object(data)) => data,
+        _ => Map::new(),
+    };
+    // Selenium reports the prompt of status 26 as `alert.text`, W3C as `data.text`
+    if let Some(text) = value.get("alert").and_then(|a| a.get("text")).cloned() {
+        data.entry("text".to_string()).or_insert(text);
+    }
+    data.extend(value);
+
+    let mut error = WebDriverError::new(error_code(status), message.as_ref().and_then(Value::as_str).unwrap_or(""));
+    error.stacktrace = match stacktrace {
+        Some(Value::String(stacktrace)) => stacktrace,
+        Some(Value::Array(frames)) => frames.iter().map(frame).collect::<Vec<_>>().join("\n"),
+        _ => String::new(),
+    };
+    if !data.is_empty() {
+        error.data = Some(data);
+    }
+    error
+}
+
+/// Formats a `{className, methodName, fileName, lineNumber}` stack frame of a Java remote end.
+fn frame(frame: &Value) -> String {
+    let member = |name: &str| match frame.get(name) {
+        Some(Value::String(member)) => member.clone(),
+        Some(member) => member.to_string(),
+        None => String::new(),
+    };
+    format!("at {}.{}({}:{})",
+            member("className"),
+            member("methodName"),
+            member("fileName"),
+            member("lineNumber"))
+}
@@ -0,0 +1,317 @@
+use crate::structs::WebDriverError;
+
+use std::fmt::Display;
+use std::fmt::Error;
+use std::fmt::Formatter;
+
+/// Special keys from the W3C WebDriver keyboard table, mapped to their
+/// Unicode private-use code points.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Key {
+    Null,
+    Cancel,
+    Help,
+    Backspace,
+    Tab,
+    Clear,
+    Return,
+    Enter,
+    Shift,
+    Control,
+    Alt,
+    Pause,
+    Escape,
+    Space,
+    PageUp,
+    PageDown,
+    End,
+    Home,
+    Left,
+    Up,
+    Right,
+    Down,
+    Insert,
+    Delete,
+    Semicolon,
+    Equals,
+    Numpad0,
+    Numpad1,
+    Numpad2,
+    Numpad3,
+    Numpad4,
+    Numpad5,
+    Numpad6,
+    Numpad7,
+    Numpad8,
+    Numpad9,
+    Multiply,
+    Add,
+    Separator,
+    Subtract,
+    Decimal,
+    Divide,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    Meta,
+    ZenkakuHankaku,
+    RightShift,
+    RightControl,
+    RightAlt,
+    RightMeta,
+    NumpadPageUp,
+    NumpadPageDown,
+    NumpadEnd,
+    NumpadHome,
+    NumpadLeft,
+    NumpadUp,
+    NumpadRight,
+    NumpadDown,
+    NumpadInsert,
+    NumpadDelete,
+}
+
+impl Key {
+    pub const ALL: [Key; 70] = [
+        Key::Null, Key::Cancel, Key::Help, Key::Backspace, Key::Tab, Key::Clear,
+        Key::Return, Key::Enter, Key::Shift, Key::Control, Key::Alt, Key::Pause,
+        Key::Escape, Key::Space, Key::PageUp, Key::PageDown, Key::End, Key::Home,
+        Key::Left, Key::Up, Key::Right, Key::Down, Key::Insert, Key::Delete,
+        Key::Semicolon, Key::Equals, Key::Numpad0, Key::Numpad1, Key::Numpad2,
+        Key::Numpad3, Key::Numpad4, Key::Numpad5, Key::Numpad6, Key::Numpad7,
+        Key::Numpad8, Key::Numpad9, Key::Multiply, Key::Add, Key::Separator,
+        Key::Subtract, Key::Decimal, Key::Divide, Key::F1, Key::F2, Key::F3,
+        Key::F4, Key::F5, Key::F6, Key::F7, Key::F8, Key::F9, Key::F10, Key::F11,
+        Key::F12, Key::Meta, Key::ZenkakuHankaku, Key::RightShift,
+        Key::RightControl, Key::RightAlt, Key::RightMeta, Key::NumpadPageUp,
+        Key::NumpadPageDown, Key::NumpadEnd, Key::NumpadHome, Key::NumpadLeft,
+        Key::NumpadUp, Key::NumpadRight, Key::NumpadDown, Key::NumpadInsert,
+        Key::NumpadDelete,
+    ];
+
+    pub fn code_point(self) -> char {
+        match self {
+            Key::Null => '\u{E000}',
+            Key::Cancel => '\u{E001}',
+            Key::Help => '\u{E002}',
+            Key::Backspace => '\u{E003}',
+            Key::Tab => '\u{E004}',
+            Key::Clear => '\u{E005}',
+            Key::Return => '\u{E006}',
+            Key::Enter => '\u{E007}',
+            Key::Shift => '\u{E008}',
+            Key::Control => '\u{E009}',
+            Key::Alt => '\u{E00A}',
+            Key::Pause => '\u{E00B}',
+            Key::Escape => '\u{E00C}',
+            Key::Space => '\u{E00D}',
+            Key::PageUp => '\u{E00E}',
+            Key::PageDown => '\u{E00F}',
+            Key::End => '\u{E010}',
+            Key::Home => '\u{E011}',
+            Key::Left => '\u{E012}',
+            Key::Up => '\u{E013}',
+            Key::Right => '\u{E014}',
+            Key::Down => '\u{E015}',
+            Key::Insert => '\u{E016}',
+            Key::Delete => '\u{E017}',
+            Key::Semicolon => '\u{E018}',
+            Key::Equals => '\u{E019}',
+            Key::Numpad0 => '\u{E01A}',
+            Key::Numpad1 => '\u{E01B}',
+            Key::Numpad2 => '\u{E01C}',
+            Key::Numpad3 => '\u{E01D}',
+            Key::Numpad4 => '\u{E01E}',
+            Key::Numpad5 => '\u{E01F}',
+            Key::Numpad6 => '\u{E020}',
+            Key::Numpad7 => '\u{E021}',
+            Key::Numpad8 => '\u{E022}',
+            Key::Numpad9 => '\u{E023}',
+            Key::Multiply => '\u{E024}',
+            Key::Add => '\u{E025}',
+            Key::Separator => '\u{E026}',
+            Key::Subtract => '\u{E027}',
+            Key::Decimal => '\u{E028}',
+            Key::Divide => '\u{E029}',
+            Key::F1 => '\u{E031}',
+            Key::F2 => '\u{E032}',
+            Key::F3 => '\u{E033}',
+            Key::F4 => '\u{E034}',
+            Key::F5 => '\u{E035}',
+            Key::F6 => '\u{E036}',
+            Key::F7 => '\u{E037}',
+            Key::F8 => '\u{E038}',
+            Key::F9 => '\u{E039}',
+            Key::F10 => '\u{E03A}',
+            Key::F11 => '\u{E03B}',
+            Key::F12 => '\u{E03C}',
+            Key::Meta => '\u{E03D}',
+            Key::ZenkakuHankaku => '\u{E040}',
+            Key::RightShift => '\u{E050}',
+            Key::RightControl => '\u{E051}',
+            Key::RightAlt => '\u{E052}',
+            Key::RightMeta => '\u{E053}',
+            Key::NumpadPageUp => '\u{E054}',
+            Key::NumpadPageDown => '\u{E055}',
+            Key::NumpadEnd => '\u{E056}',
+            Key::NumpadHome => '\u{E057}',
+            Key::NumpadLeft => '\u{E058}',
+            Key::NumpadUp => '\u{E059}',
+            Key::NumpadRight => '\u{E05A}',
+            Key::NumpadDown => '\u{E05B}',
+            Key::NumpadInsert => '\u{E05C}',
+            Key::NumpadDelete => '\u{E05D}',
+        }
+    }
+
+    pub fn from_code_point(c: char) -> Option<Key> {
+        Key::ALL.iter().find(|k| k.code_point() == c).cloned()
+    }
+
+    pub fn is_modifier(self) -> bool {
+        matches!(self, Key::Shift | Key::Control | Key::Alt | Key::Meta
+            | Key::RightShift | Key::RightControl | Key::RightAlt | Key::RightMeta)
+    }
+}
+
+impl From<Key> for char {
+    fn from(key: Key) -> char {
+        key.code_point()
+    }
+}
+
+impl Display for Key {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(f, "{}", self.code_point())
+    }
+}
+
+/// Builder composing plain text, special keys and modifier chords
+/// into the string expected by `Element::send_keys`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeySequence {
+    text: String,
+}
+
+impl KeySequence {
+    pub fn new() -> Self {
+        KeySequence { text: String::new() }
+    }
+
+    pub fn text(mut self, text: &str) -> Self {
+        self.text.push_str(text);
+        self
+    }
+
+    pub fn key(mut self, key: Key) -> Self {
+        self.text.push(key.code_point());
+        self
+    }
+
+    /// Holds `modifiers` while typing `text`, then releases them with the null key.
+    pub fn chord(mut self, modifiers: &[Key], text: &str) -> Self {
+        for m in modifiers {
+            self.text.push(m.code_point());
+        }
+        self.text.push_str(text);
+        self.release()
+    }
+
+    /// Appends the null key, which releases all pressed modifiers.
+    pub fn release(self) -> Self {
+        self.key(Key::Null)
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.text.as_str()
+    }
+
+    pub fn validate(&self) -> Result<(), WebDriverError> {
+        validate(&self.text)
+    }
+}
+
+impl From<Key> for KeySequence {
+    fn from(key: Key) -> KeySequence {
+        KeySequence::new().key(key)
+    }
+}
+
+impl From<&str> for KeySequence {
+    fn from(text: &str) -> KeySequence {
+        KeySequence::new().text(text)
+    }
+}
+
+impl AsRef<str> for KeySequence {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Display for KeySequence {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(f, "{}", self.text)
+    }
+}
+
+/// Rejects code points from the private-use area which are not WebDriver keys,
+/// since drivers would otherwise fail or type them literally.
+pub fn validate(text: &str) -> Result<(), WebDriverError> {
+    match text.chars()
+        .find(|c| ('\u{E000}'..='\u{F8FF}').contains(c) && Key::from_code_point(*c).is_none()) {
+        Some(c) => Err(WebDriverError::new(
+            "invalid argument",
+            &format!("Unsupported key code point U+{:04X}", c as u32),
+        )),
+        None => Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_points_round_trip() {
+        for key in Key::ALL.iter() {
+            assert_eq!(Key::from_code_point(key.code_point()), Some(*key));
+        }
+        assert_eq!(Key::Shift.code_point(), '\u{E008}');
+        assert_eq!(Key::Control.code_point(), '\u{E009}');
+        assert_eq!(Key::Alt.code_point(), '\u{E00A}');
+        assert_eq!(Key::Meta.code_point(), '\u{E03D}');
+        assert_eq!(Key::RightMeta.code_point(), '\u{E053}');
+        assert_eq!(Key::from_code_point('\u{E030}'), None);
+        assert_eq!(Key::ALL.iter().filter(|k| k.is_modifier()).count(), 8);
+    }
+
+    #[test]
+    fn chords_are_released_with_the_null_key() {
+        let keys = KeySequence::new()
+            .chord(&[Key::Control, Key::Shift], "t")
+            .text("ab")
+            .key(Key::Enter);
+        assert_eq!(keys.as_str(), "\u{E009}\u{E008}t\u{E000}ab\u{E007}");
+        assert_eq!(KeySequence::from(Key::Meta).release().as_str(), "\u{E03D}\u{E000}");
+        assert_eq!(KeySequence::from("a").to_string(), "a");
+    }
+
+    #[test]
+    fn unknown_private_use_code_points_are_rejected() {
+        assert!(validate("plain \u{E007} text \u{F900}").is_ok());
+        let error = validate("a\u{E030}").unwrap_err();
+        assert_eq!(error.error, "invalid argument");
+        assert_eq!(error.message, "Unsupported key code point U+E030");
+        assert!(KeySequence::new().text("\u{F8FF}").validate().is_err());
+    }
+}
@@ -1,11 +1,37 @@
 
 #[macro_use]
 extern crate serde_derive;
+extern crate base64;
 extern crate serde;
 extern crate serde_json;
+extern crate log;
 
+pub mod alert;
+pub mod by;
+pub mod cassette;
+pub mod chromium;
+pub mod firefox;
+pub mod grid;
 pub mod httpdecorator;
+pub mod jsonwire;
+pub mod keys;
+pub mod logs;
+pub mod pool;
+pub mod relative;
+pub mod retry;
+pub mod scope;
 pub mod traits;
 pub mod structs;
+pub mod trace;
+pub mod webauthn;
+
+use serde_json::Value;
 
 pub const WEB_ELEMENT_IDENTIFIER: &str = "element-6066-11e4-a52e-4f735466cecf";
+
+/// Reference id of a serialized element, accepting the legacy `ELEMENT` key as well.
+pub fn element_reference(value: &Value) -> Option<&str> {
+    value.get(WEB_ELEMENT_IDENTIFIER)
+        .or_else(|| value.get(jsonwire::ELEMENT_IDENTIFIER))
+        .and_then(Value::as_str)
+}
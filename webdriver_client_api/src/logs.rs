@@ -0,0 +1,147 @@
+use crate::structs::ExecuteScript;
+use crate::structs::WebDriverError;
+use crate::traits::HttpExecutor;
+use crate::traits::Session;
+
+use log::*;
+use serde_json::Map;
+use serde_json::Value;
+
+/// Legacy endpoints returning the browser log, Selenium's first.
+const LOG_ENDPOINTS: [&str; 2] = ["se/log", "log"];
+
+/// Errors with which remote ends turn down commands they do not implement.
+const UNSUPPORTED: [&str; 3] = ["unknown command", "unknown method", "unsupported operation"];
+
+/// Installs the console and error hook in the current document unless present, then drains it.
+const HOOK_SCRIPT: &str = r#"
+if (!window.__webdriverLogs) {
+    var logs = window.__webdriverLogs = [];
+    var push = function (level, message, source) {
+        logs.push({level: level, message: message, source: source, timestamp: Date.now()});
+    };
+    var levels = {debug: 'DEBUG', log: 'INFO', info: 'INFO', warn: 'WARNING', error: 'SEVERE'};
+    Object.keys(levels).forEach(function (name) {
+        var original = console[name];
+        console[name] = function () {
+            push(levels[name], Array.prototype.map.call(arguments, function (arg) {
+                try {
+                    var text = typeof arg === 'string' ? arg : JSON.stringify(arg);
+                    return text === undefined ? String(arg) : text;
+                } catch (e) {
+                    return String(arg);
+                }
+            }).join(' '), 'console-api');
+            return original.apply(console, arguments);
+        };
+    });
+    window.addEventListener('error', function (e) {
+        push('SEVERE', e.message + (e.filename ? ' (' + e.filename + ':' + e.lineno + ')' : ''), 'javascript');
+    });
+    window.addEventListener('unhandledrejection', function (e) {
+        push('SEVERE', 'Uncaught (in promise) ' + String(e.reason), 'javascript');
+    });
+}
+return window.__webdriverLogs.splice(0);
+"#;
+
+/// Severity of a browser log entry, from least to most severe.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum LogLevel {
+    #[serde(alias = "ALL", alias = "FINEST", alias = "FINER", alias = "FINE", alias = "CONFIG")]
+    Debug,
+    Info,
+    Warning,
+    Severe,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LogEntry {
+    pub level: LogLevel,
+    pub message: String,
+    /// Where the entry came from, e.g. `console-api`, `javascript` or `network`.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    Endpoint(&'static str),
+    Hook,
+}
+
+/// Browser console entries logged since the capture started.
+///
+/// Reads the legacy log endpoints where the remote end has them. Elsewhere a hook on `console`
+/// and `window.onerror` is installed with `execute_sync`; it lives in the current document, so
+/// it is installed again by every `entries` call and misses what a new page logs before that.
+pub struct LogCapture<'a, H: HttpExecutor> {
+    session: &'a dyn Session<H>,
+    mode: Mode,
+}
+
+impl<'a, H: HttpExecutor> LogCapture<'a, H> {
+    /// Starts capturing, preferring the log endpoints. Earlier entries are dropped.
+    pub fn start(session: &'a dyn Session<H>) -> Result<Self, WebDriverError> {
+        for &endpoint in LOG_ENDPOINTS.iter() {
+            let capture = LogCapture { session, mode: Mode::Endpoint(endpoint) };
+            match capture.entries() {
+                Ok(_) => return Ok(capture),
+                Err(ref e) if UNSUPPORTED.contains(&e.error.as_str()) =>
+                    debug!("No browser log at {}: {}", endpoint, e.message),
+                Err(e) => return Err(e),
+            }
+        }
+        LogCapture::hook(session)
+    }
+
+    /// Starts capturing through the injected hook, even where log endpoints exist.
+    pub fn hook(session: &'a dyn Session<H>) -> Result<Self, WebDriverError> {
+        let capture = LogCapture { session, mode: Mode::Hook };
+        capture.entries()?;
+        Ok(capture)
+    }
+
+    /// Whether entries come from the injected hook rather than a log endpoint.
+    pub fn is_hooked(&self) -> bool {
+        self.mode == Mode::Hook
+    }
+
+    /// Entries logged since the last call, which are not returned again.
+    pub fn entries(&self) -> Result<Vec<LogEntry>, WebDriverError> {
+        let value = match self.mode {
+            Mode::Endpoint(endpoint) => {
+                let mut body = Map::new();
+                body.insert("type".to_string(), Value::String("browser".to_string()));
+                self.session.get_http_executor().post::<Value, _>(endpoint, body)?
+            }
+            Mode::Hook => self.session.execute_sync(ExecuteScript {
+                script: HOOK_SCRIPT.to_string(),
+                args: Vec::new(),
+            })?,
+        };
+        serde_json::from_value(value).map_err(|e| WebDriverError::new(
+            "unknown error",
+            &format!("Unexpected browser log entries: {}", e),
+        ))
+    }
+
+    /// Drains the entries, failing with `javascript error` if any of them is severe.
+    pub fn assert_no_severe_errors(&self) -> Result<(), WebDriverError> {
+        let severe: Vec<String> = self.entries()?
+            .into_iter()
+            .filter(|entry| entry.level == LogLevel::Severe)
+            .map(|entry| entry.message)
+            .collect();
+        if severe.is_empty() {
+            return Ok(());
+        }
+        Err(WebDriverError::new(
+            "javascript error",
+            &format!("{} severe browser log entries: {}", severe.len(), severe.join("; ")),
+        ))
+    }
+}
@@ -0,0 +1,222 @@
+use crate::alert::NO_SUCH_ALERT;
+use crate::structs::Capabilities;
+use crate::structs::WebDriverError;
+use crate::traits::HttpExecutor;
+use crate::traits::Session;
+use crate::traits::SessionHandle;
+use crate::traits::WebDriver;
+
+use log::*;
+
+use std::ops::Deref;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::time::Duration;
+use std::time::Instant;
+
+/// Upper bound of alerts dismissed while resetting, in case a page keeps opening new ones.
+const MAX_ALERTS: usize = 10;
+
+type HealthCheck<H> = Box<dyn Fn(&dyn Session<H>) -> bool + Send + Sync>;
+
+struct PoolState<H: HttpExecutor> {
+    idle: Vec<SessionHandle<H>>,
+    /// Sessions alive, whether idle or leased.
+    size: usize,
+}
+
+/// Reuses up to `max_size` sessions created with the same capabilities.
+///
+/// Sessions are created lazily by `lease`, reset when their lease is dropped and
+/// deleted when they fail the health check, a reset, or the pool itself is dropped.
+pub struct SessionPool<H: HttpExecutor, W: WebDriver<H>> {
+    driver: W,
+    caps: Capabilities,
+    max_size: usize,
+    acquire_timeout: Option<Duration>,
+    health_check: HealthCheck<H>,
+    state: Mutex<PoolState<H>>,
+    returned: Condvar,
+}
+
+impl<H: HttpExecutor, W: WebDriver<H>> SessionPool<H, W> {
+    pub fn new(driver: W, caps: Capabilities, max_size: usize) -> Self {
+        SessionPool {
+            driver,
+            caps,
+            max_size: max_size.max(1),
+            acquire_timeout: None,
+            health_check: Box::new(|session| session.get_window_handle().is_ok()),
+            state: Mutex::new(PoolState { idle: Vec::new(), size: 0 }),
+            returned: Condvar::new(),
+        }
+    }
+
+    /// Fails `lease` with `timeout` instead of waiting forever for a session to be returned.
+    pub fn with_acquire_timeout(mut self, timeout: Duration) -> Self {
+        self.acquire_timeout = Some(timeout);
+        self
+    }
+
+    /// Replaces the default check, which asks the session for its current window handle.
+    pub fn with_health_check<F>(mut self, check: F) -> Self
+        where F: Fn(&dyn Session<H>) -> bool + Send + Sync + 'static {
+        self.health_check = Box::new(check);
+        self
+    }
+
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Number of sessions alive, leased or not.
+    pub fn size(&self) -> usize {
+        self.lock().size
+    }
+
+    pub fn idle(&self) -> usize {
+        self.lock().idle.len()
+    }
+
+    /// Leases a healthy idle session, creates one if the pool is not full, or waits for a lease to end.
+    pub fn lease(&self) -> Result<Lease<'_, H, W>, WebDriverError> {
+        let deadline = self.acquire_timeout.map(|t| Instant::now() + t);
+        let mut state = self.lock();
+        loop {
+            if let Some(session) = state.idle.pop() {
+                drop(state);
+                if (self.health_check)(session.as_ref()) {
+                    return Ok(Lease { pool: self, session: Some(session) });
+                }
+                warn!("Discarding pooled session {} which failed its health check", session.get_session_id());
+                self.discard(session);
+                state = self.lock();
+                continue;
+            }
+
+            if state.size < self.max_size {
+                state.size += 1;
+                drop(state);
+                return match self.driver.create_session(self.caps.clone()) {
+                    Ok(session) => Ok(Lease { pool: self, session: Some(session) }),
+                    Err(e) => {
+                        self.lock().size -= 1;
+                        self.returned.notify_one();
+                        Err(e)
+                    }
+                };
+            }
+
+            state = match deadline {
+                None => self.returned.wait(state).unwrap_or_else(|e| e.into_inner()),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(WebDriverError::new(
+                            "timeout",
+                            &format!("No pooled session was returned within {:?}", self.acquire_timeout.unwrap()),
+                        ));
+                    }
+                    self.returned.wait_timeout(state, deadline - now)
+                        .unwrap_or_else(|e| e.into_inner())
+                        .0
+                }
+            };
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, PoolState<H>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn release(&self, session: SessionHandle<H>) {
+        match reset(session.as_ref()) {
+            Ok(()) => {
+                self.lock().idle.push(session);
+                self.returned.notify_one();
+            }
+            Err(e) => {
+                warn!("Discarding pooled session {} which could not be reset: {}", session.get_session_id(), e);
+                self.discard(session);
+            }
+        }
+    }
+
+    fn discard(&self, session: SessionHandle<H>) {
+        if let Err(e) = session.delete_session() {
+            debug!("Cannot delete discarded session {}: {}", session.get_session_id(), e);
+        }
+        self.lock().size -= 1;
+        self.returned.notify_one();
+    }
+}
+
+impl<H: HttpExecutor, W: WebDriver<H>> Drop for SessionPool<H, W> {
+    fn drop(&mut self) {
+        let idle: Vec<_> = self.lock().idle.drain(..).collect();
+        for session in idle {
+            if let Err(e) = session.delete_session() {
+                warn!("Cannot delete pooled session {}: {}", session.get_session_id(), e);
+            }
+        }
+    }
+}
+
+/// Brings a session back to a blank state: no alerts, a single window, no cookies, `about:blank`.
+pub fn reset<H: HttpExecutor>(session: &dyn Session<H>) -> Result<(), WebDriverError> {
+    for _ in 0..MAX_ALERTS {
+        match session.dismiss_alert() {
+            Ok(()) => continue,
+            Err(ref e) if e.error == NO_SUCH_ALERT => break,
+            Err(e) => return Err(e),
+        }
+    }
+
+    let handles = session.get_window_handles()?;
+    if let Some((first, extra)) = handles.split_first() {
+        for handle in extra {
+            session.switch_to_window(handle)?;
+            session.close_window()?;
+        }
+        session.switch_to_window(first)?;
+    }
+
+    session.delete_all_cookies()?;
+    session.navigate_to("about:blank")
+}
+
+/// A session leased from a `SessionPool`, returned to it when dropped.
+pub struct Lease<'a, H: HttpExecutor, W: WebDriver<H>> {
+    pool: &'a SessionPool<H, W>,
+    session: Option<SessionHandle<H>>,
+}
+
+impl<'a, H: HttpExecutor, W: WebDriver<H>> Lease<'a, H, W> {
+    pub fn session(&self) -> &SessionHandle<H> {
+        self.session.as_ref().unwrap()
+    }
+
+    /// Deletes the session instead of returning it, e.g. after it crashed.
+    pub fn discard(mut self) {
+        if let Some(session) = self.session.take() {
+            self.pool.discard(session);
+        }
+    }
+}
+
+impl<'a, H: HttpExecutor, W: WebDriver<H>> Deref for Lease<'a, H, W> {
+    type Target = dyn Session<H>;
+
+    fn deref(&self) -> &(dyn Session<H> + 'static) {
+        self.session().as_ref()
+    }
+}
+
+impl<'a, H: HttpExecutor, W: WebDriver<H>> Drop for Lease<'a, H, W> {
+    fn drop(&mut self) {
+        if let Some(session) = self.session.take() {
+            self.pool.release(session);
+        }
+    }
+}
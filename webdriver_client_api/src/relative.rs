@@ -0,0 +1,157 @@
+use crate::by::By;
+use crate::structs::ElementRect;
+use crate::structs::WebDriverError;
+use crate::traits::ElementHandle;
+use crate::traits::HttpExecutor;
+use crate::traits::Session;
+
+/// Distance in CSS pixels within which `near` matches, as in Selenium.
+pub const DEFAULT_NEAR_DISTANCE: f64 = 50.0;
+
+/// Element a relative filter is measured against, found when the locator is evaluated if needed.
+pub enum Anchor<H: HttpExecutor> {
+    Element(ElementHandle<H>),
+    Locator(By),
+}
+
+impl<H: HttpExecutor> From<By> for Anchor<H> {
+    fn from(by: By) -> Self {
+        Anchor::Locator(by)
+    }
+}
+
+impl<H: HttpExecutor> From<&By> for Anchor<H> {
+    fn from(by: &By) -> Self {
+        Anchor::Locator(by.clone())
+    }
+}
+
+impl<H: HttpExecutor> From<ElementHandle<H>> for Anchor<H> {
+    fn from(element: ElementHandle<H>) -> Self {
+        Anchor::Element(element)
+    }
+}
+
+impl<H: HttpExecutor> From<&ElementHandle<H>> for Anchor<H> {
+    fn from(element: &ElementHandle<H>) -> Self {
+        Anchor::Element(element.clone())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    Above,
+    Below,
+    LeftOf,
+    RightOf,
+    /// Within the given distance of any edge.
+    Near(f64),
+}
+
+impl Direction {
+    /// Whether `candidate` lies in this direction of `anchor`.
+    pub fn matches(&self, candidate: &ElementRect, anchor: &ElementRect) -> bool {
+        match *self {
+            Direction::Above => candidate.bottom() <= anchor.y,
+            Direction::Below => candidate.y >= anchor.bottom(),
+            Direction::LeftOf => candidate.right() <= anchor.x,
+            Direction::RightOf => candidate.x >= anchor.right(),
+            Direction::Near(distance) => gap(candidate, anchor) <= distance,
+        }
+    }
+}
+
+/// Locator filtering the elements of a base locator by their position relative to anchors,
+/// e.g. `with(By::tag_name("button")).right_of(By::id("email-label"))`.
+///
+/// Positions are compared through `Element::get_rect`, so every candidate costs a command.
+pub struct RelativeBy<H: HttpExecutor> {
+    base: By,
+    filters: Vec<(Direction, Anchor<H>)>,
+}
+
+pub fn with<H: HttpExecutor>(base: By) -> RelativeBy<H> {
+    RelativeBy { base, filters: Vec::new() }
+}
+
+impl<H: HttpExecutor> RelativeBy<H> {
+    pub fn above<A: Into<Anchor<H>>>(self, anchor: A) -> Self {
+        self.filter(Direction::Above, anchor)
+    }
+
+    pub fn below<A: Into<Anchor<H>>>(self, anchor: A) -> Self {
+        self.filter(Direction::Below, anchor)
+    }
+
+    pub fn left_of<A: Into<Anchor<H>>>(self, anchor: A) -> Self {
+        self.filter(Direction::LeftOf, anchor)
+    }
+
+    pub fn right_of<A: Into<Anchor<H>>>(self, anchor: A) -> Self {
+        self.filter(Direction::RightOf, anchor)
+    }
+
+    pub fn near<A: Into<Anchor<H>>>(self, anchor: A) -> Self {
+        self.filter(Direction::Near(DEFAULT_NEAR_DISTANCE), anchor)
+    }
+
+    pub fn near_within<A: Into<Anchor<H>>>(self, anchor: A, distance: f64) -> Self {
+        self.filter(Direction::Near(distance), anchor)
+    }
+
+    pub fn filter<A: Into<Anchor<H>>>(mut self, direction: Direction, anchor: A) -> Self {
+        self.filters.push((direction, anchor.into()));
+        self
+    }
+
+    /// Elements of the base locator matching every filter, closest to the first anchor first.
+    pub fn find_all<S: Session<H> + ?Sized>(&self, session: &S) -> Result<Vec<ElementHandle<H>>, WebDriverError> {
+        let mut anchors = Vec::with_capacity(self.filters.len());
+        for (direction, anchor) in &self.filters {
+            let element = match anchor {
+                Anchor::Element(element) => element.clone(),
+                Anchor::Locator(by) => session.find_element_by(by)?,
+            };
+            let rect = element.get_rect()?;
+            anchors.push((*direction, element.get_reference_id(), rect));
+        }
+
+        let mut matches = Vec::new();
+        for candidate in session.find_elements_by(&self.base)? {
+            if anchors.iter().any(|(_, id, _)| *id == candidate.get_reference_id()) {
+                continue;
+            }
+            let rect = candidate.get_rect()?;
+            if anchors.iter().all(|(direction, _, anchor)| direction.matches(&rect, anchor)) {
+                let distance = anchors.first().map(|(_, _, anchor)| center_distance(&rect, anchor)).unwrap_or(0.0);
+                matches.push((distance, candidate));
+            }
+        }
+        matches.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        Ok(matches.into_iter().map(|(_, element)| element).collect())
+    }
+
+    /// Closest matching element, failing with `no such element` if there is none.
+    pub fn find<S: Session<H> + ?Sized>(&self, session: &S) -> Result<ElementHandle<H>, WebDriverError> {
+        self.find_all(session)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| WebDriverError::new(
+                "no such element",
+                &format!("No element {} matches the relative filters", self.base),
+            ))
+    }
+}
+
+fn center_distance(a: &ElementRect, b: &ElementRect) -> f64 {
+    let (ax, ay) = a.center();
+    let (bx, by) = b.center();
+    (ax - bx).hypot(ay - by)
+}
+
+/// Shortest distance between the edges of two rectangles, zero if they overlap.
+fn gap(a: &ElementRect, b: &ElementRect) -> f64 {
+    let dx = (b.x - a.right()).max(a.x - b.right()).max(0.0);
+    let dy = (b.y - a.bottom()).max(a.y - b.bottom()).max(0.0);
+    dx.hypot(dy)
+}
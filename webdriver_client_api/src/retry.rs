@@ -0,0 +1,190 @@
+use crate::structs::WebDriverError;
+use crate::structs::TRANSPORT_ERROR;
+use crate::traits::HttpExecutor;
+
+use log::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::thread;
+use std::time::Duration;
+
+/// Which failed commands are retried, how often and how long to wait in between.
+///
+/// GET commands are retried on matching errors; other commands only when opted in
+/// with `retry_command`, as replaying e.g. `click` may repeat a side effect.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    /// Fraction of each delay randomly cut off, from 0 (none) to 1.
+    pub jitter: f64,
+    /// Makes the jitter of each attempt reproducible, it is random per delay if unset.
+    pub jitter_seed: Option<u64>,
+    /// Waits between attempts, `thread::sleep` by default.
+    pub sleeper: fn(Duration),
+    pub error_codes: Vec<String>,
+    /// HTTP statuses, as recorded in `WebDriverError.data["status"]` by the backend for every
+    /// error read from a response.
+    pub http_statuses: Vec<u64>,
+    pub commands: Vec<(String, String)>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            initial_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+            jitter: 0.5,
+            jitter_seed: None,
+            sleeper: thread::sleep,
+            error_codes: vec![TRANSPORT_ERROR.to_string()],
+            http_statuses: vec![502, 503, 504],
+            commands: Vec::new(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        RetryPolicy::default()
+    }
+
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn delays(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_delay = initial;
+        self.max_delay = max;
+        self
+    }
+
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn jitter_seed(mut self, seed: u64) -> Self {
+        self.jitter_seed = Some(seed);
+        self
+    }
+
+    /// Replaces `thread::sleep`, e.g. to record the delays in tests instead of waiting.
+    pub fn sleeper(mut self, sleeper: fn(Duration)) -> Self {
+        self.sleeper = sleeper;
+        self
+    }
+
+    pub fn retry_on_error(mut self, error: &str) -> Self {
+        self.error_codes.push(error.to_string());
+        self
+    }
+
+    pub fn retry_on_status(mut self, status: u64) -> Self {
+        self.http_statuses.push(status);
+        self
+    }
+
+    /// Opts a non-GET command in, matched by method and trailing path, e.g. `("POST", "url")`.
+    pub fn retry_command(mut self, method: &str, path: &str) -> Self {
+        self.commands.push((method.to_string(), path.trim_matches('/').to_string()));
+        self
+    }
+
+    fn is_retryable_command(&self, method: &str, path: &str) -> bool {
+        method == "GET" || self.commands.iter().any(|(m, suffix)|
+            m == method && (path == suffix || path.ends_with(&format!("/{}", suffix))))
+    }
+
+    fn is_retryable_error(&self, error: &WebDriverError) -> bool {
+        let status = error.data.as_ref()
+            .and_then(|d| d.get("status"))
+            .and_then(Value::as_u64);
+        self.error_codes.contains(&error.error)
+            || status.is_some_and(|s| self.http_statuses.contains(&s))
+    }
+
+    /// Wait before retrying after failed attempt number `attempt`, counted from 0.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exponential = self.initial_delay
+            .checked_mul(1 << attempt.min(16))
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+        let hash = match self.jitter_seed {
+            Some(seed) => {
+                let mut hasher = DefaultHasher::new();
+                (seed, attempt).hash(&mut hasher);
+                hasher.finish()
+            }
+            None => RandomState::new().hash_one(attempt),
+        };
+        let random = hash as f64 / u64::MAX as f64;
+        exponential.mul_f64(1.0 - self.jitter * random)
+    }
+}
+
+/// Decorator retrying commands which failed transiently, with exponential backoff and jitter.
+#[derive(Clone)]
+pub struct RetryExecutor<H: HttpExecutor> {
+    policy: RetryPolicy,
+    inner: Box<H>,
+}
+
+impl<H: HttpExecutor> RetryExecutor<H> {
+    pub fn new(inner: Box<H>, policy: RetryPolicy) -> Self {
+        RetryExecutor { policy, inner }
+    }
+
+    pub fn into_inner(self) -> Box<H> {
+        self.inner
+    }
+
+    fn with_retry<T, F>(&self, method: &str, path: &str, command: F) -> Result<T, WebDriverError>
+        where F: Fn(&H) -> Result<T, WebDriverError>
+    {
+        let retryable = self.policy.is_retryable_command(method, path);
+        let mut attempt = 0;
+        loop {
+            match command(&self.inner) {
+                Err(e) if retryable && attempt < self.policy.max_retries && self.policy.is_retryable_error(&e) => {
+                    let delay = self.policy.delay(attempt);
+                    attempt += 1;
+                    warn!("Retrying {} {} in {:?} ({}/{}) after {}",
+                          method, path, delay, attempt, self.policy.max_retries, e.message);
+                    (self.policy.sleeper)(delay);
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl<H: HttpExecutor> HttpExecutor for RetryExecutor<H> {
+    fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, WebDriverError> {
+        self.with_retry("GET", path, |http| http.get(path))
+    }
+
+    fn post<T: DeserializeOwned, S: Serialize>(&self, path: &str, body: S) -> Result<T, WebDriverError> {
+        let body = serde_json::to_value(body)
+            .map_err(|e| WebDriverError::new("invalid argument", &e.to_string()))?;
+        self.with_retry("POST", path, |http| http.post(path, &body))
+    }
+
+    fn delete<T: DeserializeOwned>(&self, path: &str) -> Result<T, WebDriverError> {
+        self.with_retry("DELETE", path, |http| http.delete(path))
+    }
+
+    fn is_local(&self) -> bool {
+        self.inner.is_local()
+    }
+}
@@ -0,0 +1,91 @@
+use crate::structs::Frame;
+use crate::structs::WebDriverError;
+use crate::traits::HttpExecutor;
+use crate::traits::Session;
+
+use log::*;
+
+use std::marker::PhantomData;
+
+/// Runs closures in another frame or window and switches back afterwards, even if they fail
+/// or panic. Implemented for every session, `SessionHandle`s included.
+pub trait ScopedSession<H: HttpExecutor>: Session<H> {
+    /// Runs `f` inside `frame`, e.g. `session.in_frame(By::id("editor"), |s| s.get_title())`,
+    /// then returns to the frame path the session was in before.
+    fn in_frame<F, R>(&self, frame: F, f: impl FnOnce(&Self) -> Result<R, WebDriverError>) -> Result<R, WebDriverError>
+    where
+        F: Into<Frame<H>>,
+    {
+        let frames = self.get_frame_path();
+        self.switch_to_frame(frame.into())?;
+        let guard = Restore { session: self, window: None, frames, done: false, http: PhantomData };
+        let result = f(self);
+        guard.finish(result)
+    }
+
+    /// Runs `f` in the window with `handle`, then returns to the previous window and frame path.
+    fn in_window<R>(&self, handle: &str, f: impl FnOnce(&Self) -> Result<R, WebDriverError>) -> Result<R, WebDriverError> {
+        let window = self.get_window_handle()?;
+        let frames = self.get_frame_path();
+        self.switch_to_window(handle)?;
+        let guard = Restore { session: self, window: Some(window), frames, done: false, http: PhantomData };
+        let result = f(self);
+        guard.finish(result)
+    }
+}
+
+impl<H: HttpExecutor, S: Session<H> + ?Sized> ScopedSession<H> for S {}
+
+/// Switches back to `window` and `frames` when finished, or when dropped during a panic.
+struct Restore<'a, H: HttpExecutor, S: Session<H> + ?Sized> {
+    session: &'a S,
+    window: Option<String>,
+    frames: Vec<Frame<H>>,
+    done: bool,
+    http: PhantomData<H>,
+}
+
+impl<'a, H: HttpExecutor, S: Session<H> + ?Sized> Restore<'a, H, S> {
+    /// Restores, keeping the closure's error over a failure to restore.
+    fn finish<R>(mut self, result: Result<R, WebDriverError>) -> Result<R, WebDriverError> {
+        self.done = true;
+        match (result, self.restore()) {
+            (Ok(value), restored) => restored.map(|_| value),
+            (Err(e), Ok(())) => Err(e),
+            (Err(e), Err(restore)) => {
+                error!("Cannot restore browsing context after {}: {}", e, restore);
+                Err(e)
+            }
+        }
+    }
+
+    fn restore(&self) -> Result<(), WebDriverError> {
+        match &self.window {
+            Some(window) => self.session.switch_to_window(window)?,
+            None => {
+                let current = self.session.get_frame_path();
+                if current == self.frames {
+                    return Ok(());
+                }
+                if current.len() == self.frames.len() + 1 && current.starts_with(&self.frames) {
+                    return self.session.switch_to_parent();
+                }
+                self.session.switch_to_frame(Frame::None)?;
+            }
+        }
+        for frame in &self.frames {
+            self.session.switch_to_frame(frame.clone())?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, H: HttpExecutor, S: Session<H> + ?Sized> Drop for Restore<'a, H, S> {
+    fn drop(&mut self) {
+        if !self.done {
+            if let Err(e) = self.restore() {
+                error!("Cannot restore browsing context: {}", e);
+            }
+        }
+    }
+}
@@ -1,3 +1,7 @@
+use crate::by::By;
+use crate::traits::ElementHandle;
+use crate::traits::HttpExecutor;
+
 use serde_json::Map;
 use serde_json::Value;
 use std::fmt::Display;
@@ -8,12 +12,65 @@ pub type Capabilities = Map<String, Value>;
 pub type Cookies = Vec<Cookie>;
 
 
-pub enum Frame {
+/// Frame to switch to, or `None` for the top-level browsing context.
+#[derive(Clone)]
+pub enum Frame<H: HttpExecutor> {
+    /// Raw reference id of a frame element.
     Elem(String),
+    Element(ElementHandle<H>),
+    /// Frame element found when switching.
+    Locator(By),
     Id(i32),
     None,
 }
 
+impl<H: HttpExecutor> PartialEq for Frame<H> {
+    /// Frame elements are equal when they share a reference id, however they are held.
+    fn eq(&self, other: &Self) -> bool {
+        let reference = |frame: &Frame<H>| match frame {
+            Frame::Elem(id) => Some(id.clone()),
+            Frame::Element(element) => Some(element.get_reference_id()),
+            _ => None,
+        };
+        match (self, other) {
+            (Frame::Locator(a), Frame::Locator(b)) => a == b,
+            (Frame::Id(a), Frame::Id(b)) => a == b,
+            (Frame::None, Frame::None) => true,
+            _ => reference(self).is_some() && reference(self) == reference(other),
+        }
+    }
+}
+
+impl<H: HttpExecutor> From<ElementHandle<H>> for Frame<H> {
+    fn from(element: ElementHandle<H>) -> Self {
+        Frame::Element(element)
+    }
+}
+
+impl<H: HttpExecutor> From<&ElementHandle<H>> for Frame<H> {
+    fn from(element: &ElementHandle<H>) -> Self {
+        Frame::Element(element.clone())
+    }
+}
+
+impl<H: HttpExecutor> From<By> for Frame<H> {
+    fn from(by: By) -> Self {
+        Frame::Locator(by)
+    }
+}
+
+impl<H: HttpExecutor> From<&By> for Frame<H> {
+    fn from(by: &By) -> Self {
+        Frame::Locator(by.clone())
+    }
+}
+
+impl<H: HttpExecutor> From<i32> for Frame<H> {
+    fn from(index: i32) -> Self {
+        Frame::Id(index)
+    }
+}
+
 pub enum WebDriverLocator {
     ID,
     CSS,
@@ -44,23 +101,164 @@ pub struct Timeouts {
     pub implicit: i32,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Rect {
+/// Element bounds in CSS pixels, which may be fractional.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ElementRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl ElementRect {
+    pub fn right(&self) -> f64 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> f64 {
+        self.y + self.height
+    }
+
+    pub fn center(&self) -> (f64, f64) {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+
+    /// Whether `other` lies within these bounds, edges included.
+    pub fn contains(&self, other: &ElementRect) -> bool {
+        other.x >= self.x && other.y >= self.y && other.right() <= self.right() && other.bottom() <= self.bottom()
+    }
+
+    pub fn contains_point(&self, x: f64, y: f64) -> bool {
+        x >= self.x && y >= self.y && x <= self.right() && y <= self.bottom()
+    }
+
+    /// Whether both share some area; rects only touching at an edge do not.
+    pub fn intersects(&self, other: &ElementRect) -> bool {
+        self.x < other.right() && other.x < self.right() && self.y < other.bottom() && other.y < self.bottom()
+    }
+}
+
+impl From<WindowRect> for ElementRect {
+    fn from(rect: WindowRect) -> Self {
+        ElementRect {
+            x: rect.x as f64,
+            y: rect.y as f64,
+            width: rect.width as f64,
+            height: rect.height as f64,
+        }
+    }
+}
+
+/// Window position on the screen and outer size, in whole pixels.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowRect {
     pub x: i32,
     pub y: i32,
     pub width: i32,
     pub height: i32,
 }
 
+/// Body of `set_window_rect`; fields left out keep their current value.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SetWindowRect {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<i32>,
+}
+
+impl SetWindowRect {
+    pub fn resize(width: i32, height: i32) -> Self {
+        SetWindowRect { width: Some(width), height: Some(height), ..SetWindowRect::default() }
+    }
+
+    pub fn move_to(x: i32, y: i32) -> Self {
+        SetWindowRect { x: Some(x), y: Some(y), ..SetWindowRect::default() }
+    }
+}
+
+impl From<WindowRect> for SetWindowRect {
+    fn from(rect: WindowRect) -> Self {
+        SetWindowRect { x: Some(rect.x), y: Some(rect.y), width: Some(rect.width), height: Some(rect.height) }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ExecuteScript {
     pub script: String,
     pub args: Vec<Value>,
 }
 
+/// Permission named as in the Permissions registry, e.g. `geolocation`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PermissionDescriptor {
+    pub name: String,
+    /// Further members of some descriptors, like `sysex` for `midi`.
+    #[serde(flatten)]
+    pub options: Map<String, Value>,
+}
+
+impl PermissionDescriptor {
+    pub fn new(name: &str) -> Self {
+        PermissionDescriptor { name: name.to_string(), options: Map::new() }
+    }
+
+    pub fn geolocation() -> Self {
+        PermissionDescriptor::new("geolocation")
+    }
+
+    pub fn notifications() -> Self {
+        PermissionDescriptor::new("notifications")
+    }
+
+    pub fn camera() -> Self {
+        PermissionDescriptor::new("camera")
+    }
+
+    pub fn microphone() -> Self {
+        PermissionDescriptor::new("microphone")
+    }
+
+    pub fn clipboard_read() -> Self {
+        PermissionDescriptor::new("clipboard-read")
+    }
+
+    pub fn clipboard_write() -> Self {
+        PermissionDescriptor::new("clipboard-write")
+    }
+
+    pub fn midi(sysex: bool) -> Self {
+        PermissionDescriptor::new("midi").option("sysex", Value::Bool(sysex))
+    }
+
+    pub fn push(user_visible_only: bool) -> Self {
+        PermissionDescriptor::new("push").option("userVisibleOnly", Value::Bool(user_visible_only))
+    }
+
+    pub fn option(mut self, name: &str, value: Value) -> Self {
+        self.options.insert(name.to_string(), value);
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionState {
+    Granted,
+    Denied,
+    Prompt,
+}
+
+
+/// Client-side error code for requests which never got a response from the remote end.
+pub const TRANSPORT_ERROR: &str = "transport error";
 
 //TODO add ErrorKind
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct WebDriverError {
     pub error: String,
     pub message: String,
@@ -69,6 +267,26 @@ pub struct WebDriverError {
     pub data: Option<Map<String, Value>>,
 }
 
+impl WebDriverError {
+    /// Builds a client-side error, which carries no stacktrace or data from the driver.
+    pub fn new(error: &str, message: &str) -> Self {
+        WebDriverError {
+            error: error.to_string(),
+            message: message.to_string(),
+            stacktrace: String::new(),
+            data: None,
+        }
+    }
+
+    /// Text of the prompt an `unexpected alert open` error reports, if the driver included it.
+    pub fn alert_text(&self) -> Option<&str> {
+        if self.error != crate::alert::UNEXPECTED_ALERT_OPEN {
+            return None;
+        }
+        self.data.as_ref()?.get("text")?.as_str()
+    }
+}
+
 impl Display for WebDriverError {
     fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
         //TODO write stacktrace and data
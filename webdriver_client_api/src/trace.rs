@@ -0,0 +1,173 @@
+use crate::structs::WebDriverError;
+use crate::traits::HttpExecutor;
+
+use log::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+const REDACTED: &str = "***";
+
+/// One executed command, with sensitive body fields already redacted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommandEvent {
+    pub method: String,
+    pub path: String,
+    pub session_id: Option<String>,
+    pub element_id: Option<String>,
+    pub body: Option<Value>,
+    pub duration: Duration,
+    /// WebDriver error code if the command failed.
+    pub error: Option<String>,
+}
+
+impl CommandEvent {
+    pub fn outcome(&self) -> &str {
+        if self.error.is_some() { "error" } else { "ok" }
+    }
+}
+
+/// Receives an event for every command passing through a `TracingExecutor`.
+pub trait CommandSink: Send + Sync {
+    fn record(&self, event: &CommandEvent);
+}
+
+impl<F: Fn(&CommandEvent) + Send + Sync> CommandSink for F {
+    fn record(&self, event: &CommandEvent) {
+        self(event)
+    }
+}
+
+/// Writes events to the `webdriver::command` log target.
+pub struct LogSink;
+
+impl CommandSink for LogSink {
+    fn record(&self, event: &CommandEvent) {
+        info!(target: "webdriver::command",
+              "method={} path={} session={} element={} duration_ms={} outcome={} error={} body={}",
+              event.method,
+              event.path,
+              event.session_id.as_deref().unwrap_or("-"),
+              event.element_id.as_deref().unwrap_or("-"),
+              event.duration.as_millis(),
+              event.outcome(),
+              event.error.as_deref().unwrap_or("-"),
+              event.body.as_ref().map(Value::to_string).unwrap_or_else(|| "-".to_string()));
+    }
+}
+
+/// Decorator emitting a `CommandEvent` with timing and outcome for each command.
+///
+/// By default the typed `text` of `element/*/value` and `alert/text`, uploaded files, added
+/// cookies and the private keys of WebAuthn credentials are redacted.
+#[derive(Clone)]
+pub struct TracingExecutor<H: HttpExecutor> {
+    inner: Box<H>,
+    sink: Arc<dyn CommandSink>,
+    redactions: Vec<(String, String)>,
+}
+
+impl<H: HttpExecutor> TracingExecutor<H> {
+    pub fn new(inner: Box<H>) -> Self {
+        TracingExecutor {
+            inner,
+            sink: Arc::new(LogSink),
+            redactions: vec![
+                ("element/*/value".to_string(), "text".to_string()),
+                ("alert/text".to_string(), "text".to_string()),
+                ("se/file".to_string(), "file".to_string()),
+                ("cookie".to_string(), "cookie".to_string()),
+                ("webauthn/authenticator/*/credential".to_string(), "privateKey".to_string()),
+            ],
+        }
+    }
+
+    pub fn with_sink(mut self, sink: Arc<dyn CommandSink>) -> Self {
+        self.sink = sink;
+        self
+    }
+
+    /// Hides `field` of bodies sent to paths ending with `pattern`, where `*` matches one segment.
+    pub fn redact(mut self, pattern: &str, field: &str) -> Self {
+        self.redactions.push((pattern.trim_matches('/').to_string(), field.to_string()));
+        self
+    }
+
+    pub fn clear_redactions(mut self) -> Self {
+        self.redactions.clear();
+        self
+    }
+
+    pub fn into_inner(self) -> Box<H> {
+        self.inner
+    }
+
+    fn trace<T, F>(&self, method: &str, path: &str, body: Option<&Value>, command: F) -> Result<T, WebDriverError>
+        where F: FnOnce(&H) -> Result<T, WebDriverError>
+    {
+        let start = Instant::now();
+        let result = command(&self.inner);
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        self.sink.record(&CommandEvent {
+            method: method.to_string(),
+            path: path.to_string(),
+            session_id: segment_after(&segments, "session"),
+            element_id: segment_after(&segments, "element").filter(|id| id != "active"),
+            body: body.map(|b| self.redacted(&segments, b)),
+            duration: start.elapsed(),
+            error: result.as_ref().err().map(|e| e.error.clone()),
+        });
+        result
+    }
+
+    fn redacted(&self, segments: &[&str], body: &Value) -> Value {
+        let mut body = body.clone();
+        for (pattern, field) in &self.redactions {
+            if ends_with_pattern(segments, pattern) {
+                if let Some(value) = body.get_mut(field.as_str()) {
+                    *value = Value::String(REDACTED.to_string());
+                }
+            }
+        }
+        body
+    }
+}
+
+impl<H: HttpExecutor> HttpExecutor for TracingExecutor<H> {
+    fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, WebDriverError> {
+        self.trace("GET", path, None, |http| http.get(path))
+    }
+
+    fn post<T: DeserializeOwned, S: Serialize>(&self, path: &str, body: S) -> Result<T, WebDriverError> {
+        let body = serde_json::to_value(body)
+            .map_err(|e| WebDriverError::new("invalid argument", &e.to_string()))?;
+        self.trace("POST", path, Some(&body), |http| http.post(path, &body))
+    }
+
+    fn delete<T: DeserializeOwned>(&self, path: &str) -> Result<T, WebDriverError> {
+        self.trace("DELETE", path, None, |http| http.delete(path))
+    }
+
+    fn is_local(&self) -> bool {
+        self.inner.is_local()
+    }
+}
+
+fn segment_after(segments: &[&str], name: &str) -> Option<String> {
+    segments.iter()
+        .position(|s| *s == name)
+        .and_then(|i| segments.get(i + 1))
+        .map(|s| s.to_string())
+}
+
+fn ends_with_pattern(segments: &[&str], pattern: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    segments.len() >= pattern.len()
+        && segments[segments.len() - pattern.len()..].iter()
+        .zip(pattern.iter())
+        .all(|(s, p)| *p == "*" || s == p)
+}
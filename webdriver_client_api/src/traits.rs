@@ -1,8 +1,14 @@
 extern crate serde_json;
 
 
+use crate::alert::Alert;
+use crate::by::By;
 use crate::structs::*;
 use crate::httpdecorator::HttpExecutorPathDecorator;
+use crate::keys::KeySequence;
+use crate::logs::LogCapture;
+use crate::webauthn::VirtualAuthenticator;
+use crate::webauthn::VirtualAuthenticatorOptions;
 
 use serde_json::Value;
 //TODO make webdriver specific
@@ -10,6 +16,14 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 
 use std::clone::Clone;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Shareable session, cheap to clone and usable from any thread.
+pub type SessionHandle<H> = Arc<dyn Session<H>>;
+/// Shareable element, cheap to clone and usable from any thread.
+pub type ElementHandle<H> = Arc<dyn Element<H>>;
 
 pub trait LocatorStrategy {
     fn get_using_str(&self) -> &str;
@@ -29,9 +43,19 @@ impl LocatorStrategy for WebDriverLocator {
 }
 
 //TODO add drop for session
-pub trait Session<H: HttpExecutor> {
-    fn get_session_id(self) -> String;
-    fn get_http_executor(self) -> Box<HttpExecutorPathDecorator<H>>;
+pub trait Session<H: HttpExecutor>: Send + Sync {
+    fn get_session_id(&self) -> &str;
+    /// Capabilities the remote end returned when creating the session.
+    fn get_capabilities(&self) -> &Capabilities;
+    /// Chrome DevTools websocket URL exposed by a Selenium grid.
+    fn se_cdp_url(&self) -> Option<&str> {
+        self.get_capabilities().get("se:cdp").and_then(Value::as_str)
+    }
+    /// VNC websocket URL exposed by a Selenium grid node with VNC enabled.
+    fn se_vnc_url(&self) -> Option<&str> {
+        self.get_capabilities().get("se:vnc").and_then(Value::as_str)
+    }
+    fn get_http_executor(&self) -> &HttpExecutorPathDecorator<H>;
     fn delete_session(&self) -> Result<(), WebDriverError>;
     fn get_timeouts(&self) -> Result<Timeouts, WebDriverError>;
     fn set_timeouts(&self, timeouts: Timeouts) -> Result<(), WebDriverError>;
@@ -45,24 +69,34 @@ pub trait Session<H: HttpExecutor> {
     fn close_window(&self) -> Result<Vec<String>, WebDriverError>;
     fn switch_to_window(&self, handle: &str) -> Result<(), WebDriverError>;
     fn get_window_handles(&self) -> Result<Vec<String>, WebDriverError>;
-    fn switch_to_frame(&self, frame: Frame) -> Result<(), WebDriverError>;
+    fn switch_to_frame(&self, frame: Frame<H>) -> Result<(), WebDriverError>;
     fn switch_to_parent(&self) -> Result<(), WebDriverError>;
-    fn get_window_rect(&self) -> Result<Rect, WebDriverError>;
-    fn set_window_rect(&self, rect: Rect) -> Result<Rect, WebDriverError>;
-    fn maximize(&self) -> Result<Rect, WebDriverError>;
-    fn minimize(&self) -> Result<Rect, WebDriverError>;
-    fn fullscreen(&self) -> Result<Rect, WebDriverError>;
-    fn get_active_element(&self) -> Result<Box<dyn Element<H>>, WebDriverError>;
+    /// Frames entered below the top-level browsing context, as tracked by this session.
+    /// Locators are stored as the elements they found.
+    fn get_frame_path(&self) -> Vec<Frame<H>>;
+    fn get_window_rect(&self) -> Result<WindowRect, WebDriverError>;
+    /// Moves and resizes the window, sending only the fields which are set.
+    fn set_window_rect(&self, rect: SetWindowRect) -> Result<WindowRect, WebDriverError>;
+    fn maximize(&self) -> Result<WindowRect, WebDriverError>;
+    fn minimize(&self) -> Result<WindowRect, WebDriverError>;
+    fn fullscreen(&self) -> Result<WindowRect, WebDriverError>;
+    fn get_active_element(&self) -> Result<ElementHandle<H>, WebDriverError>;
     fn find_element(
         &self,
         using: &dyn LocatorStrategy,
         value: &str,
-    ) -> Result<Box<dyn Element<H>>, WebDriverError>;
+    ) -> Result<ElementHandle<H>, WebDriverError>;
     fn find_elements(
         &self,
         using: &dyn LocatorStrategy,
         value: &str,
-    ) -> Result<Vec<Box<dyn Element<H>>>, WebDriverError>;
+    ) -> Result<Vec<ElementHandle<H>>, WebDriverError>;
+    fn find_element_by(&self, by: &By) -> Result<ElementHandle<H>, WebDriverError> {
+        self.find_element(by, by.value())
+    }
+    fn find_elements_by(&self, by: &By) -> Result<Vec<ElementHandle<H>>, WebDriverError> {
+        self.find_elements(by, by.value())
+    }
     fn get_page_source(&self) -> Result<String, WebDriverError>;
     //TODO maybe extract and make generic
     fn execute_sync(&self, script: ExecuteScript) -> Result<Value, WebDriverError>;
@@ -79,47 +113,88 @@ pub trait Session<H: HttpExecutor> {
     fn accept_alert(&self) -> Result<(), WebDriverError>;
     fn get_alert_text(&self) -> Result<String, WebDriverError>;
     fn set_alert_text(&self, text: &str) -> Result<(), WebDriverError>;
+    /// Handle to the prompt open right now, failing with `no such alert` if there is none.
+    fn alert(&self) -> Result<Alert<'_, H>, WebDriverError>;
+    /// Handle to the first prompt opened within `timeout`.
+    fn wait_for_alert(&self, timeout: Duration) -> Result<Alert<'_, H>, WebDriverError>;
     fn take_screenshot(&self) -> Result<String, WebDriverError>;
+    /// Sets a permission of the current page's origin, as defined by the Permissions spec.
+    fn set_permission(&self, descriptor: &PermissionDescriptor, state: PermissionState) -> Result<(), WebDriverError>;
+    /// Adds a WebAuthn virtual authenticator, which answers the page's credential requests.
+    fn add_virtual_authenticator(
+        &self,
+        options: &VirtualAuthenticatorOptions,
+    ) -> Result<VirtualAuthenticator<'_, H>, WebDriverError>;
+    /// Starts capturing the browser console, see `LogCapture`.
+    fn capture_logs(&self) -> Result<LogCapture<'_, H>, WebDriverError>;
 }
 
-pub trait Element<H: HttpExecutor> {
-    fn get_reference_id(&self) -> &str;
-    fn get_http_executor(self) -> Box<HttpExecutorPathDecorator<HttpExecutorPathDecorator<H>>>;
+pub trait Element<H: HttpExecutor>: Send + Sync {
+    /// Current reference id, which changes when the element is relocated after going stale.
+    fn get_reference_id(&self) -> String;
+    fn get_http_executor(&self) -> Arc<HttpExecutorPathDecorator<HttpExecutorPathDecorator<H>>>;
+    /// Same element with relocation switched on or off. Relocating elements remember the locators
+    /// which found them and retry a command once, after finding the element again, if it failed
+    /// with a stale element reference. On by default; turn it off where a replay is unsafe.
+    fn relocating(&self, enabled: bool) -> ElementHandle<H>;
+    /// Same element with state-changing commands such as `click`, `clear` and `send_keys` also
+    /// retried after relocation. Off by default, as the element found again may not be the one
+    /// the action was meant for.
+    fn replaying(&self, enabled: bool) -> ElementHandle<H>;
     fn find_element(
         &self,
         using: &dyn LocatorStrategy,
         value: &str,
-    ) -> Result<Box<dyn Element<H>>, WebDriverError>;
+    ) -> Result<ElementHandle<H>, WebDriverError>;
     fn find_elements(
         &self,
         using: &dyn LocatorStrategy,
         value: &str,
-    ) -> Result<Vec<Box<dyn Element<H>>>, WebDriverError>;
+    ) -> Result<Vec<ElementHandle<H>>, WebDriverError>;
+    fn find_element_by(&self, by: &By) -> Result<ElementHandle<H>, WebDriverError> {
+        self.find_element(by, by.value())
+    }
+    fn find_elements_by(&self, by: &By) -> Result<Vec<ElementHandle<H>>, WebDriverError> {
+        self.find_elements(by, by.value())
+    }
     fn is_selected(&self) -> Result<bool, WebDriverError>;
     fn get_attribute(&self, name: &str) -> Result<String, WebDriverError>;
     fn get_property(&self, name: &str) -> Result<String, WebDriverError>;
     fn get_css_value(&self, name: &str) -> Result<String, WebDriverError>;
     fn get_text(&self) -> Result<String, WebDriverError>;
     fn get_tag_name(&self) -> Result<String, WebDriverError>;
-    fn get_rect(&self) -> Result<Rect, WebDriverError>;
+    fn get_rect(&self) -> Result<ElementRect, WebDriverError>;
     fn is_enabled(&self) -> Result<bool, WebDriverError>;
     fn click(&self) -> Result<(), WebDriverError>;
     fn clear(&self) -> Result<(), WebDriverError>;
-    //TODO check param text in runtime
+    /// Types `text`, rejecting private-use code points which are not WebDriver keys.
     fn send_keys(&self, text: &str) -> Result<(), WebDriverError>;
+    fn send_key_sequence(&self, keys: &KeySequence) -> Result<(), WebDriverError> {
+        self.send_keys(keys.as_str())
+    }
 
     fn take_screenshot(&self) -> Result<String, WebDriverError>;
+    /// Sets the file of an `<input type=file>`, transferring it to the remote end if needed.
+    fn upload_file(&self, path: &Path) -> Result<(), WebDriverError> {
+        self.upload_files(&[path])
+    }
+    fn upload_files(&self, paths: &[&Path]) -> Result<(), WebDriverError>;
 }
 
 pub trait WebDriver<H: HttpExecutor> {
-    fn create_session(self, caps: Capabilities) -> Result<Box<dyn Session<H>>, WebDriverError>;
-    fn get_http_executor(self) -> Box<H>;
+    fn create_session(&self, caps: Capabilities) -> Result<SessionHandle<H>, WebDriverError>;
+    fn get_http_executor(&self) -> &H;
     fn status(&self) -> Status;
 }
 
-pub trait HttpExecutor: Clone {
+pub trait HttpExecutor: Clone + Send + Sync + 'static {
     fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, WebDriverError>;
     fn post<T: DeserializeOwned, S: Serialize>(&self, path: &str, body: S) -> Result<T, WebDriverError>;
     fn delete<T: DeserializeOwned>(&self, path: &str) -> Result<T, WebDriverError>;
+    /// Whether the remote end runs on this machine, so local file paths are valid for it.
+    /// Executors which cannot tell answer `false`, making uploads go through `se/file`.
+    fn is_local(&self) -> bool {
+        false
+    }
 }
 
@@ -0,0 +1,228 @@
+use crate::structs::WebDriverError;
+use crate::traits::HttpExecutor;
+use crate::traits::Session;
+
+use serde_json::Map;
+use serde_json::Value;
+
+/// Protocol a virtual authenticator speaks.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Protocol {
+    #[serde(rename = "ctap1/u2f")]
+    Ctap1U2f,
+    #[serde(rename = "ctap2")]
+    Ctap2,
+    #[serde(rename = "ctap2_1")]
+    Ctap21,
+}
+
+/// How a virtual authenticator is attached to the client.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Transport {
+    Usb,
+    Nfc,
+    Ble,
+    SmartCard,
+    Hybrid,
+    Internal,
+}
+
+/// Capabilities of a virtual authenticator, defaulting to a consenting CTAP2 USB key
+/// without resident keys or user verification.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualAuthenticatorOptions {
+    pub protocol: Protocol,
+    pub transport: Transport,
+    pub has_resident_key: bool,
+    pub has_user_verification: bool,
+    pub is_user_consenting: bool,
+    pub is_user_verified: bool,
+}
+
+impl Default for VirtualAuthenticatorOptions {
+    fn default() -> Self {
+        VirtualAuthenticatorOptions {
+            protocol: Protocol::Ctap2,
+            transport: Transport::Usb,
+            has_resident_key: false,
+            has_user_verification: false,
+            is_user_consenting: true,
+            is_user_verified: false,
+        }
+    }
+}
+
+impl VirtualAuthenticatorOptions {
+    pub fn new() -> Self {
+        VirtualAuthenticatorOptions::default()
+    }
+
+    pub fn protocol(mut self, protocol: Protocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    pub fn transport(mut self, transport: Transport) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Whether discoverable credentials, as used by passkeys, can be stored.
+    pub fn resident_key(mut self, enabled: bool) -> Self {
+        self.has_resident_key = enabled;
+        self
+    }
+
+    pub fn user_verification(mut self, enabled: bool) -> Self {
+        self.has_user_verification = enabled;
+        self
+    }
+
+    pub fn user_consenting(mut self, consenting: bool) -> Self {
+        self.is_user_consenting = consenting;
+        self
+    }
+
+    pub fn user_verified(mut self, verified: bool) -> Self {
+        self.is_user_verified = verified;
+        self
+    }
+}
+
+/// Public key credential held by a virtual authenticator. Binary members travel base64url-encoded.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Credential {
+    #[serde(with = "base64url")]
+    pub credential_id: Vec<u8>,
+    pub is_resident_credential: bool,
+    pub rp_id: String,
+    /// PKCS#8 encoded private key.
+    #[serde(with = "base64url")]
+    pub private_key: Vec<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "base64url::option")]
+    pub user_handle: Option<Vec<u8>>,
+    pub sign_count: u32,
+}
+
+impl Credential {
+    /// Discoverable credential, bound to the user it was created for.
+    pub fn resident(id: &[u8], rp_id: &str, private_key: &[u8], user_handle: &[u8], sign_count: u32) -> Self {
+        Credential {
+            credential_id: id.to_vec(),
+            is_resident_credential: true,
+            rp_id: rp_id.to_string(),
+            private_key: private_key.to_vec(),
+            user_handle: Some(user_handle.to_vec()),
+            sign_count,
+        }
+    }
+
+    pub fn non_resident(id: &[u8], rp_id: &str, private_key: &[u8], sign_count: u32) -> Self {
+        Credential {
+            credential_id: id.to_vec(),
+            is_resident_credential: false,
+            rp_id: rp_id.to_string(),
+            private_key: private_key.to_vec(),
+            user_handle: None,
+            sign_count,
+        }
+    }
+}
+
+/// Authenticator added to a session through the WebAuthn extension commands.
+/// It stays until `remove` is called or the session ends.
+pub struct VirtualAuthenticator<'a, H: HttpExecutor> {
+    session: &'a dyn Session<H>,
+    id: String,
+}
+
+impl<'a, H: HttpExecutor> VirtualAuthenticator<'a, H> {
+    pub fn add(session: &'a dyn Session<H>, options: &VirtualAuthenticatorOptions) -> Result<Self, WebDriverError> {
+        let id = session.get_http_executor().post("webauthn/authenticator", options)?;
+        Ok(VirtualAuthenticator { session, id })
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn add_credential(&self, credential: &Credential) -> Result<(), WebDriverError> {
+        self.session.get_http_executor().post(&self.path("credential"), credential)
+    }
+
+    pub fn get_credentials(&self) -> Result<Vec<Credential>, WebDriverError> {
+        self.session.get_http_executor().get(&self.path("credentials"))
+    }
+
+    pub fn remove_credential(&self, credential_id: &[u8]) -> Result<(), WebDriverError> {
+        let path = self.path(&format!("credentials/{}", base64url::encode(credential_id)));
+        self.session.get_http_executor().delete(&path)
+    }
+
+    pub fn remove_all_credentials(&self) -> Result<(), WebDriverError> {
+        self.session.get_http_executor().delete(&self.path("credentials"))
+    }
+
+    /// Whether user verification, e.g. a fingerprint, succeeds from now on.
+    pub fn set_user_verified(&self, verified: bool) -> Result<(), WebDriverError> {
+        let mut body = Map::new();
+        body.insert("isUserVerified".to_string(), Value::Bool(verified));
+        self.session.get_http_executor().post(&self.path("uv"), body)
+    }
+
+    pub fn remove(self) -> Result<(), WebDriverError> {
+        self.session.get_http_executor().delete(&format!("webauthn/authenticator/{}", self.id))
+    }
+
+    fn path(&self, command: &str) -> String {
+        format!("webauthn/authenticator/{}/{}", self.id, command)
+    }
+}
+
+/// Unpadded URL-safe base64, as WebAuthn uses for binary data.
+pub mod base64url {
+    use serde::de::Error;
+    use serde::Deserialize;
+    use serde::Deserializer;
+    use serde::Serializer;
+
+    pub fn encode(bytes: &[u8]) -> String {
+        base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
+    }
+
+    /// Decodes, tolerating trailing padding.
+    pub fn decode(text: &str) -> Result<Vec<u8>, base64::DecodeError> {
+        base64::decode_config(text.trim_end_matches('='), base64::URL_SAFE_NO_PAD)
+    }
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        decode(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+
+    pub mod option {
+        use serde::Deserialize;
+        use serde::Deserializer;
+        use serde::Serializer;
+
+        pub fn serialize<S: Serializer>(bytes: &Option<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error> {
+            match bytes {
+                Some(bytes) => super::serialize(bytes, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error> {
+            match Option::<String>::deserialize(deserializer)? {
+                Some(text) => super::decode(&text).map(Some).map_err(serde::de::Error::custom),
+                None => Ok(None),
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use serde_json::Value;
+
+/// Entry of the `log.entryAdded` event, for console calls and uncaught errors.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    /// `debug`, `info`, `warn` or `error`.
+    pub level: String,
+    #[serde(default)]
+    pub text: Option<String>,
+    pub timestamp: u64,
+    pub source: Value,
+    /// `console` or `javascript`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub method: Option<String>,
+    #[serde(default)]
+    pub args: Vec<Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NavigationInfo {
+    pub context: String,
+    #[serde(default)]
+    pub navigation: Option<String>,
+    pub timestamp: u64,
+    pub url: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BrowsingContextInfo {
+    pub context: String,
+    pub url: String,
+    #[serde(default)]
+    pub parent: Option<String>,
+    #[serde(default)]
+    pub children: Option<Vec<BrowsingContextInfo>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestData {
+    pub request: String,
+    pub url: String,
+    pub method: String,
+    #[serde(default)]
+    pub headers: Vec<Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseData {
+    pub url: String,
+    pub status: u16,
+    #[serde(default)]
+    pub status_text: String,
+    #[serde(default)]
+    pub mime_type: String,
+    #[serde(default)]
+    pub headers: Vec<Value>,
+}
+
+/// Parameters shared by the `network.*` events.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkEvent {
+    #[serde(default)]
+    pub context: Option<String>,
+    #[serde(default)]
+    pub navigation: Option<String>,
+    #[serde(default)]
+    pub redirect_count: u32,
+    pub request: RequestData,
+    pub timestamp: u64,
+    #[serde(default)]
+    pub response: Option<ResponseData>,
+}
+
+/// Event pushed by the remote end for a subscribed event name.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    LogEntryAdded(LogEntry),
+    ContextCreated(BrowsingContextInfo),
+    ContextDestroyed(BrowsingContextInfo),
+    NavigationStarted(NavigationInfo),
+    DomContentLoaded(NavigationInfo),
+    Load(NavigationInfo),
+    BeforeRequestSent(NetworkEvent),
+    ResponseStarted(NetworkEvent),
+    ResponseCompleted(NetworkEvent),
+    FetchError(NetworkEvent),
+    /// Event without a typed representation, or whose parameters did not match it.
+    Other { method: String, params: Value },
+}
+
+impl Event {
+    pub(crate) fn parse(method: &str, params: Value) -> Event {
+        fn typed<T: serde::de::DeserializeOwned>(params: &Value, f: fn(T) -> Event) -> Option<Event> {
+            serde_json::from_value(params.clone()).ok().map(f)
+        }
+        let event = match method {
+            "log.entryAdded" => typed(&params, Event::LogEntryAdded),
+            "browsingContext.contextCreated" => typed(&params, Event::ContextCreated),
+            "browsingContext.contextDestroyed" => typed(&params, Event::ContextDestroyed),
+            "browsingContext.navigationStarted" => typed(&params, Event::NavigationStarted),
+            "browsingContext.domContentLoaded" => typed(&params, Event::DomContentLoaded),
+            "browsingContext.load" => typed(&params, Event::Load),
+            "network.beforeRequestSent" => typed(&params, Event::BeforeRequestSent),
+            "network.responseStarted" => typed(&params, Event::ResponseStarted),
+            "network.responseCompleted" => typed(&params, Event::ResponseCompleted),
+            "network.fetchError" => typed(&params, Event::FetchError),
+            _ => None,
+        };
+        event.unwrap_or_else(|| Event::Other { method: method.to_string(), params })
+    }
+}
@@ -0,0 +1,273 @@
+extern crate log;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+extern crate tungstenite;
+extern crate webdriver_client_api as wda;
+
+pub mod events;
+pub mod modules;
+
+use log::*;
+use serde_json::Map;
+use serde_json::Value;
+
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::Sender;
+use std::sync::mpsc::TryRecvError;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::Message;
+use tungstenite::WebSocket;
+
+use wda::structs::Capabilities;
+use wda::structs::WebDriverError;
+use wda::structs::TRANSPORT_ERROR;
+use wda::traits::HttpExecutor;
+use wda::traits::Session;
+
+use crate::events::Event;
+use crate::modules::BrowsingContextModule;
+use crate::modules::LogModule;
+use crate::modules::NetworkModule;
+use crate::modules::ScriptModule;
+use crate::modules::SessionModule;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+type Reply = Sender<Result<Value, WebDriverError>>;
+
+struct Outgoing {
+    id: u64,
+    payload: String,
+    reply: Reply,
+}
+
+/// Asks for a BiDi websocket in a new session body, so `BiDiClient::from_session` can connect to it.
+pub fn enable_bidi(caps: &mut Capabilities) {
+    let always_match = caps.entry("capabilities".to_string())
+        .or_insert_with(|| Value::Object(Map::new()))
+        .as_object_mut()
+        .map(|c| c.entry("alwaysMatch".to_string()).or_insert_with(|| Value::Object(Map::new())));
+    if let Some(Value::Object(always_match)) = always_match {
+        always_match.insert("webSocketUrl".to_string(), Value::Bool(true));
+    }
+}
+
+/// Connection to a WebDriver BiDi websocket.
+///
+/// Commands block until their response arrives, while events are broadcast
+/// to every receiver returned by `events`. The connection closes when dropped.
+pub struct BiDiClient {
+    outgoing: Mutex<Sender<Outgoing>>,
+    subscribers: Arc<Mutex<Vec<Sender<Event>>>>,
+    next_id: AtomicU64,
+    timeout: Duration,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl BiDiClient {
+    pub fn connect(url: &str) -> Result<Self, WebDriverError> {
+        let (socket, _) = tungstenite::connect(url)
+            .map_err(|e| WebDriverError::new(TRANSPORT_ERROR, &format!("Cannot connect to {}: {}", url, e)))?;
+        // The worker polls for commands between reads, which a blocking read would stall forever
+        match socket.get_ref() {
+            MaybeTlsStream::Plain(stream) => stream.set_read_timeout(Some(POLL_INTERVAL))
+                .map_err(|e| WebDriverError::new(TRANSPORT_ERROR, &e.to_string()))?,
+            _ => return Err(WebDriverError::new(
+                "unsupported operation",
+                &format!("Cannot connect to {}: only plain ws:// connections are supported", url),
+            )),
+        }
+        info!("Connected to BiDi endpoint {}", url);
+
+        let (outgoing, commands) = mpsc::channel();
+        let subscribers = Arc::new(Mutex::new(Vec::new()));
+        let worker = {
+            let subscribers = subscribers.clone();
+            thread::spawn(move || run(socket, commands, subscribers))
+        };
+        Ok(BiDiClient {
+            outgoing: Mutex::new(outgoing),
+            subscribers,
+            next_id: AtomicU64::new(1),
+            timeout: Duration::from_secs(30),
+            worker: Some(worker),
+        })
+    }
+
+    /// Connects to the `webSocketUrl` returned for a session created with `enable_bidi`.
+    pub fn from_session<H: HttpExecutor>(session: &dyn Session<H>) -> Result<Self, WebDriverError> {
+        match session.get_capabilities().get("webSocketUrl").and_then(Value::as_str) {
+            Some(url) => BiDiClient::connect(url),
+            None => Err(WebDriverError::new(
+                "session not created",
+                "Session has no webSocketUrl capability, create it with enable_bidi",
+            )),
+        }
+    }
+
+    /// How long commands wait for their response, 30 seconds by default.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    pub fn command(&self, method: &str, params: Value) -> Result<Value, WebDriverError> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let mut message = Map::new();
+        message.insert("id".to_string(), Value::from(id));
+        message.insert("method".to_string(), Value::String(method.to_string()));
+        message.insert("params".to_string(), params);
+        let payload = Value::Object(message).to_string();
+        debug!("Sending BiDi command {}", payload);
+
+        let (reply, response) = mpsc::channel();
+        self.outgoing.lock().unwrap_or_else(|e| e.into_inner())
+            .send(Outgoing { id, payload, reply })
+            .map_err(|_| closed())?;
+        match response.recv_timeout(self.timeout) {
+            Ok(result) => result,
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(WebDriverError::new(
+                "timeout",
+                &format!("No response to {} within {:?}", method, self.timeout),
+            )),
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(closed()),
+        }
+    }
+
+    /// New receiver of all events arriving from now on; subscribe to them with `session().subscribe`.
+    pub fn events(&self) -> Receiver<Event> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.lock().unwrap_or_else(|e| e.into_inner()).push(sender);
+        receiver
+    }
+
+    pub fn session(&self) -> SessionModule<'_> {
+        SessionModule::new(self)
+    }
+
+    pub fn browsing_context(&self) -> BrowsingContextModule<'_> {
+        BrowsingContextModule::new(self)
+    }
+
+    pub fn script(&self) -> ScriptModule<'_> {
+        ScriptModule::new(self)
+    }
+
+    pub fn log(&self) -> LogModule<'_> {
+        LogModule::new(self)
+    }
+
+    pub fn network(&self) -> NetworkModule<'_> {
+        NetworkModule::new(self)
+    }
+}
+
+impl Drop for BiDiClient {
+    fn drop(&mut self) {
+        // Dropping the sender makes the worker close the socket
+        let (closed, _) = mpsc::channel();
+        *self.outgoing.lock().unwrap_or_else(|e| e.into_inner()) = closed;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn closed() -> WebDriverError {
+    WebDriverError::new(TRANSPORT_ERROR, "BiDi connection is closed")
+}
+
+fn run(mut socket: WebSocket<MaybeTlsStream<TcpStream>>,
+       commands: Receiver<Outgoing>,
+       subscribers: Arc<Mutex<Vec<Sender<Event>>>>) {
+    let mut pending: HashMap<u64, Reply> = HashMap::new();
+    loop {
+        loop {
+            match commands.try_recv() {
+                Ok(command) => match socket.send(Message::Text(command.payload)) {
+                    Ok(()) => {
+                        pending.insert(command.id, command.reply);
+                    }
+                    Err(e) => {
+                        let _ = command.reply.send(Err(WebDriverError::new(TRANSPORT_ERROR, &e.to_string())));
+                    }
+                },
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    debug!("Closing BiDi connection");
+                    let _ = socket.close(None);
+                    let _ = socket.flush();
+                    return;
+                }
+            }
+        }
+
+        match socket.read() {
+            Ok(Message::Text(text)) => dispatch(&text, &mut pending, &subscribers),
+            Ok(Message::Close(_)) => break,
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(ref e))
+            if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => {}
+            Err(e) => {
+                error!("BiDi connection failed: {}", e);
+                break;
+            }
+        }
+    }
+    for (_, reply) in pending.drain() {
+        let _ = reply.send(Err(closed()));
+    }
+}
+
+fn dispatch(text: &str, pending: &mut HashMap<u64, Reply>, subscribers: &Mutex<Vec<Sender<Event>>>) {
+    trace!("Received BiDi message {}", text);
+    let mut message: Value = match serde_json::from_str(text) {
+        Ok(message) => message,
+        Err(e) => {
+            warn!("Ignoring malformed BiDi message: {}", e);
+            return;
+        }
+    };
+    let id = message.get("id").and_then(Value::as_u64);
+    let kind = message.get("type").and_then(Value::as_str).unwrap_or("").to_string();
+    match (id, kind.as_str()) {
+        (Some(id), "success") => {
+            if let Some(reply) = pending.remove(&id) {
+                let _ = reply.send(Ok(message.get_mut("result").map(Value::take).unwrap_or(Value::Null)));
+            }
+        }
+        (Some(id), "error") => {
+            if let Some(reply) = pending.remove(&id) {
+                let error = serde_json::from_value::<WebDriverError>(message.clone()).unwrap_or_else(|_| WebDriverError {
+                    error: message.get("error").and_then(Value::as_str).unwrap_or("unknown error").to_string(),
+                    message: message.get("message").and_then(Value::as_str).unwrap_or("").to_string(),
+                    stacktrace: String::new(),
+                    data: None,
+                });
+                let _ = reply.send(Err(error));
+            }
+        }
+        _ => match message.get("method").and_then(Value::as_str) {
+            Some(method) => {
+                let params = message.get("params").cloned().unwrap_or(Value::Null);
+                let event = Event::parse(method, params);
+                subscribers.lock().unwrap_or_else(|e| e.into_inner())
+                    .retain(|s| s.send(event.clone()).is_ok());
+            }
+            None => warn!("Ignoring unexpected BiDi message {}", text),
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use crate::events::BrowsingContextInfo;
+use crate::BiDiClient;
+
+use serde_json::Map;
+use serde_json::Value;
+
+use wda::structs::Status;
+use wda::structs::WebDriverError;
+
+fn params(entries: Vec<(&str, Value)>) -> Value {
+    Value::Object(entries.into_iter()
+        .filter(|(_, v)| !v.is_null())
+        .map(|(k, v)| (k.to_string(), v))
+        .collect::<Map<String, Value>>())
+}
+
+fn strings(values: &[&str]) -> Value {
+    Value::from(values.iter().map(|v| v.to_string()).collect::<Vec<String>>())
+}
+
+fn decode<T: serde::de::DeserializeOwned>(value: Value) -> Result<T, WebDriverError> {
+    serde_json::from_value(value)
+        .map_err(|e| WebDriverError::new("unknown error", &format!("Cannot read BiDi result: {}", e)))
+}
+
+fn context_target(context: &str) -> Value {
+    params(vec![("context", Value::from(context))])
+}
+
+/// Commands of the `session` module.
+pub struct SessionModule<'a> {
+    client: &'a BiDiClient,
+}
+
+impl<'a> SessionModule<'a> {
+    pub(crate) fn new(client: &'a BiDiClient) -> Self {
+        SessionModule { client }
+    }
+
+    pub fn status(&self) -> Result<Status, WebDriverError> {
+        decode(self.client.command("session.status", params(vec![]))?)
+    }
+
+    /// Subscribes to event names or whole modules, in all contexts if `contexts` is empty.
+    pub fn subscribe(&self, events: &[&str], contexts: &[&str]) -> Result<(), WebDriverError> {
+        self.client.command("session.subscribe", params(vec![
+            ("events", strings(events)),
+            ("contexts", if contexts.is_empty() { Value::Null } else { strings(contexts) }),
+        ])).map(|_| ())
+    }
+
+    pub fn unsubscribe(&self, events: &[&str], contexts: &[&str]) -> Result<(), WebDriverError> {
+        self.client.command("session.unsubscribe", params(vec![
+            ("events", strings(events)),
+            ("contexts", if contexts.is_empty() { Value::Null } else { strings(contexts) }),
+        ])).map(|_| ())
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct NavigateResult {
+    #[serde(default)]
+    pub navigation: Option<String>,
+    pub url: String,
+}
+
+/// Commands of the `browsingContext` module.
+pub struct BrowsingContextModule<'a> {
+    client: &'a BiDiClient,
+}
+
+impl<'a> BrowsingContextModule<'a> {
+    pub(crate) fn new(client: &'a BiDiClient) -> Self {
+        BrowsingContextModule { client }
+    }
+
+    pub fn get_tree(&self, max_depth: Option<u32>) -> Result<Vec<BrowsingContextInfo>, WebDriverError> {
+        let mut result = self.client.command("browsingContext.getTree", params(vec![
+            ("maxDepth", max_depth.map(Value::from).unwrap_or(Value::Null)),
+        ]))?;
+        decode(result.get_mut("contexts").map(Value::take).unwrap_or(Value::Null))
+    }
+
+    /// Opens a `tab` or `window`, returning its context id.
+    pub fn create(&self, kind: &str) -> Result<String, WebDriverError> {
+        let mut result = self.client.command("browsingContext.create", params(vec![
+            ("type", Value::from(kind)),
+        ]))?;
+        decode(result.get_mut("context").map(Value::take).unwrap_or(Value::Null))
+    }
+
+    /// Navigates and waits for the `none`, `interactive` or `complete` readiness state.
+    pub fn navigate(&self, context: &str, url: &str, wait: Option<&str>) -> Result<NavigateResult, WebDriverError> {
+        decode(self.client.command("browsingContext.navigate", params(vec![
+            ("context", Value::from(context)),
+            ("url", Value::from(url)),
+            ("wait", wait.map(Value::from).unwrap_or(Value::Null)),
+        ]))?)
+    }
+
+    pub fn reload(&self, context: &str) -> Result<(), WebDriverError> {
+        self.client.command("browsingContext.reload", context_target(context)).map(|_| ())
+    }
+
+    pub fn close(&self, context: &str) -> Result<(), WebDriverError> {
+        self.client.command("browsingContext.close", context_target(context)).map(|_| ())
+    }
+
+    /// Base64-encoded PNG of the context viewport.
+    pub fn capture_screenshot(&self, context: &str) -> Result<String, WebDriverError> {
+        let mut result = self.client.command("browsingContext.captureScreenshot", context_target(context))?;
+        decode(result.get_mut("data").map(Value::take).unwrap_or(Value::Null))
+    }
+}
+
+/// Commands of the `script` module.
+pub struct ScriptModule<'a> {
+    client: &'a BiDiClient,
+}
+
+impl<'a> ScriptModule<'a> {
+    pub(crate) fn new(client: &'a BiDiClient) -> Self {
+        ScriptModule { client }
+    }
+
+    /// Evaluates `expression` in `context`, returning the serialized remote value.
+    ///
+    /// Script exceptions are reported as `javascript error`.
+    pub fn evaluate(&self, context: &str, expression: &str, await_promise: bool) -> Result<Value, WebDriverError> {
+        evaluation(self.client.command("script.evaluate", params(vec![
+            ("expression", Value::from(expression)),
+            ("target", context_target(context)),
+            ("awaitPromise", Value::Bool(await_promise)),
+        ]))?)
+    }
+
+    /// Calls `function_declaration` with `arguments` given as BiDi local values.
+    pub fn call_function(&self,
+                         context: &str,
+                         function_declaration: &str,
+                         arguments: Vec<Value>,
+                         await_promise: bool) -> Result<Value, WebDriverError> {
+        evaluation(self.client.command("script.callFunction", params(vec![
+            ("functionDeclaration", Value::from(function_declaration)),
+            ("target", context_target(context)),
+            ("arguments", Value::from(arguments)),
+            ("awaitPromise", Value::Bool(await_promise)),
+        ]))?)
+    }
+}
+
+fn evaluation(mut result: Value) -> Result<Value, WebDriverError> {
+    match result.get("type").and_then(Value::as_str) {
+        Some("exception") => {
+            let text = result.pointer("/exceptionDetails/text")
+                .and_then(Value::as_str)
+                .unwrap_or("Script threw an exception")
+                .to_string();
+            let mut error = WebDriverError::new("javascript error", &text);
+            error.data = result.get("exceptionDetails").and_then(Value::as_object).cloned();
+            Err(error)
+        }
+        _ => Ok(result.get_mut("result").map(Value::take).unwrap_or(Value::Null)),
+    }
+}
+
+/// Events of the `log` module, received as `Event::LogEntryAdded`.
+pub struct LogModule<'a> {
+    client: &'a BiDiClient,
+}
+
+impl<'a> LogModule<'a> {
+    pub(crate) fn new(client: &'a BiDiClient) -> Self {
+        LogModule { client }
+    }
+
+    pub fn subscribe(&self) -> Result<(), WebDriverError> {
+        self.client.session().subscribe(&["log.entryAdded"], &[])
+    }
+}
+
+/// Events of the `network` module, received as `Event::BeforeRequestSent` and friends.
+pub struct NetworkModule<'a> {
+    client: &'a BiDiClient,
+}
+
+impl<'a> NetworkModule<'a> {
+    pub(crate) fn new(client: &'a BiDiClient) -> Self {
+        NetworkModule { client }
+    }
+
+    pub fn subscribe(&self) -> Result<(), WebDriverError> {
+        self.client.session().subscribe(&[
+            "network.beforeRequestSent",
+            "network.responseStarted",
+            "network.responseCompleted",
+            "network.fetchError",
+        ], &[])
+    }
+}
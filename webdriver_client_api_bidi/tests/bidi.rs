@@ -0,0 +1,109 @@
+extern crate serde_json;
+extern crate tungstenite;
+extern crate webdriver_client_api_bidi as bidi;
+
+use bidi::events::Event;
+use bidi::BiDiClient;
+use serde_json::json;
+use serde_json::Value;
+use tungstenite::Message;
+
+use std::net::TcpListener;
+use std::thread;
+use std::time::Duration;
+
+/// Serves one websocket connection, answering each command with the messages `handler` returns.
+fn stand_in<F>(mut handler: F) -> String
+    where F: FnMut(&Value) -> Vec<Value> + Send + 'static
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut socket = tungstenite::accept(stream).unwrap();
+        while let Ok(Message::Text(text)) = socket.read() {
+            let command: Value = serde_json::from_str(&text).unwrap();
+            for message in handler(&command) {
+                socket.send(Message::Text(message.to_string())).unwrap();
+            }
+        }
+    });
+    url
+}
+
+fn success(command: &Value, result: Value) -> Value {
+    json!({"type": "success", "id": command["id"], "result": result})
+}
+
+#[test]
+fn commands_receive_their_results() {
+    let url = stand_in(|command| match command["method"].as_str().unwrap() {
+        "browsingContext.getTree" => vec![success(command, json!({
+            "contexts": [{"context": "ctx-1", "url": "about:blank", "children": []}]
+        }))],
+        "script.evaluate" => {
+            assert_eq!(command["params"]["target"], json!({"context": "ctx-1"}));
+            vec![success(command, json!({"type": "success", "realm": "r", "result": {"type": "number", "value": 2}}))]
+        }
+        _ => vec![json!({"type": "error", "id": command["id"], "error": "unknown command", "message": "nope"})],
+    });
+    let client = BiDiClient::connect(&url).unwrap();
+
+    let tree = client.browsing_context().get_tree(None).unwrap();
+    assert_eq!(tree[0].context, "ctx-1");
+    let value = client.script().evaluate("ctx-1", "1 + 1", false).unwrap();
+    assert_eq!(value, json!({"type": "number", "value": 2}));
+    let error = client.browsing_context().close("ctx-1").unwrap_err();
+    assert_eq!(error.error, "unknown command");
+}
+
+#[test]
+fn subscribed_events_are_broadcast() {
+    let url = stand_in(|command| {
+        assert_eq!(command["method"], "session.subscribe");
+        assert_eq!(command["params"], json!({"events": ["log.entryAdded"]}));
+        vec![
+            success(command, json!({})),
+            json!({"type": "event", "method": "log.entryAdded", "params": {
+                "level": "error", "text": "boom", "timestamp": 1, "type": "javascript",
+                "source": {"realm": "r"}
+            }}),
+            json!({"type": "event", "method": "custom.event", "params": {"x": 1}}),
+        ]
+    });
+    let client = BiDiClient::connect(&url).unwrap();
+    let events = client.events();
+
+    client.log().subscribe().unwrap();
+    match events.recv_timeout(Duration::from_secs(5)).unwrap() {
+        Event::LogEntryAdded(entry) => {
+            assert_eq!(entry.level, "error");
+            assert_eq!(entry.text.as_deref(), Some("boom"));
+        }
+        other => panic!("Unexpected event {:?}", other),
+    }
+    assert_eq!(events.recv_timeout(Duration::from_secs(5)).unwrap(),
+               Event::Other { method: "custom.event".to_string(), params: json!({"x": 1}) });
+}
+
+#[test]
+fn script_exceptions_are_errors() {
+    let url = stand_in(|command| vec![success(command, json!({
+        "type": "exception", "realm": "r",
+        "exceptionDetails": {"text": "ReferenceError: x is not defined", "lineNumber": 0}
+    }))]);
+    let client = BiDiClient::connect(&url).unwrap();
+
+    let error = client.script().evaluate("ctx-1", "x", false).unwrap_err();
+    assert_eq!(error.error, "javascript error");
+    assert_eq!(error.message, "ReferenceError: x is not defined");
+}
+
+#[test]
+fn enable_bidi_requests_websocket_url() {
+    let mut caps = json!({"capabilities": {"alwaysMatch": {"browserName": "firefox"}}})
+        .as_object().unwrap().clone();
+    bidi::enable_bidi(&mut caps);
+    assert_eq!(Value::Object(caps),
+               json!({"capabilities": {"alwaysMatch": {"browserName": "firefox", "webSocketUrl": true}}}));
+}
@@ -0,0 +1,398 @@
+use crate::state::MockAuthenticator;
+use crate::state::MockSession;
+use crate::state::MockState;
+use crate::MockResponse;
+
+use serde_json::Map;
+use serde_json::Value;
+
+use wda::WEB_ELEMENT_IDENTIFIER;
+
+/// 1x1 transparent PNG returned for screenshots.
+const SCREENSHOT: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNkYPhfDwAChwGA60e6kgAAAABJRU5ErkJggg==";
+
+type Outcome = Result<Value, MockResponse>;
+
+pub(crate) fn handle(state: &mut MockState, method: &str, path: &str, body: &Value) -> Outcome {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match (method, segments.as_slice()) {
+        ("GET", ["status"]) => {
+            let mut status = Map::new();
+            status.insert("ready".to_string(), Value::Bool(state.ready));
+            status.insert("message".to_string(), Value::String(
+                if state.ready { "mock ready" } else { "mock busy" }.to_string()));
+            Ok(Value::Object(status))
+        }
+        ("POST", ["session"]) => new_session(state, body),
+        ("DELETE", ["session", id]) => state.sessions.remove(*id)
+            .map(|_| Value::Null)
+            .ok_or_else(|| invalid_session(id)),
+        (_, ["session", id, rest @ ..]) => session_command(state, method, id, rest, body),
+        _ => Err(unknown_command(method, path)),
+    }
+}
+
+fn new_session(state: &mut MockState, body: &Value) -> Outcome {
+    let mut capabilities = body.pointer("/capabilities/alwaysMatch")
+        .or_else(|| body.get("desiredCapabilities"))
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    capabilities.entry("browserName".to_string())
+        .or_insert_with(|| Value::String("mock".to_string()));
+
+    let session_id = state.next_id("session");
+    let window = state.next_id("window");
+    state.sessions.insert(session_id.clone(), MockSession::new(capabilities.clone(), window));
+
+    let mut response = Map::new();
+    response.insert("sessionId".to_string(), Value::String(session_id));
+    response.insert("capabilities".to_string(), Value::Object(capabilities));
+    Ok(Value::Object(response))
+}
+
+fn session_command(state: &mut MockState, method: &str, id: &str, rest: &[&str], body: &Value) -> Outcome {
+    let session = state.sessions.get_mut(id).ok_or_else(|| invalid_session(id))?;
+
+    if rest.first() != Some(&"alert") {
+        // Default user prompt handler: dismiss and notify, unless asked to ignore prompts
+        let ignore = session.capabilities.get("unhandledPromptBehavior").and_then(Value::as_str) == Some("ignore");
+        if let Some(text) = if ignore { session.alert.clone() } else { session.alert.take() } {
+            if !ignore {
+                session.alert_input = None;
+            }
+            let mut data = Map::new();
+            data.insert("text".to_string(), Value::String(text));
+            return Err(MockResponse::error_with_data("unexpected alert open", "An alert was open", data));
+        }
+    }
+
+    match (method, rest) {
+        ("GET", ["timeouts"]) => Ok(Value::Object(session.timeouts.clone())),
+        ("POST", ["timeouts"]) => {
+            for (k, v) in object(body)? {
+                session.timeouts.insert(k.clone(), v.clone());
+            }
+            Ok(Value::Null)
+        }
+        ("POST", ["url"]) => {
+            let url = string_field(body, "url")?;
+            session.history.truncate(session.history_index + 1);
+            session.history.push(url);
+            session.history_index = session.history.len() - 1;
+            session.frame_path.clear();
+            Ok(Value::Null)
+        }
+        ("GET", ["url"]) => Ok(Value::String(session.current_url().to_string())),
+        ("POST", ["back"]) => {
+            session.history_index = session.history_index.saturating_sub(1);
+            session.frame_path.clear();
+            Ok(Value::Null)
+        }
+        ("POST", ["forward"]) => {
+            if session.history_index + 1 < session.history.len() {
+                session.history_index += 1;
+            }
+            session.frame_path.clear();
+            Ok(Value::Null)
+        }
+        ("POST", ["refresh"]) => {
+            session.frame_path.clear();
+            Ok(Value::Null)
+        }
+        ("GET", ["title"]) => {
+            let url = session.current_url().to_string();
+            Ok(Value::String(state.pages.get(&url).map(|p| p.title.clone()).unwrap_or_default()))
+        }
+        ("GET", ["source"]) => {
+            let url = session.current_url().to_string();
+            Ok(Value::String(state.pages.get(&url)
+                .map(|p| p.source.clone())
+                .unwrap_or_else(|| "<html><head></head><body></body></html>".to_string())))
+        }
+        ("GET", ["window"]) => session.current_window.clone()
+            .map(Value::String)
+            .ok_or_else(no_such_window),
+        ("DELETE", ["window"]) => {
+            let current = session.current_window.take().ok_or_else(no_such_window)?;
+            session.windows.retain(|w| *w != current);
+            Ok(Value::from(session.windows.clone()))
+        }
+        ("POST", ["window"]) => {
+            let handle = string_field(body, "handle")?;
+            if !session.windows.contains(&handle) {
+                return Err(no_such_window());
+            }
+            session.current_window = Some(handle);
+            session.frame_path.clear();
+            Ok(Value::Null)
+        }
+        ("GET", ["window", "handles"]) => Ok(Value::from(session.windows.clone())),
+        ("POST", ["window", "new"]) => {
+            let handle = state.next_id("window");
+            let session = state.sessions.get_mut(id).ok_or_else(|| invalid_session(id))?;
+            session.windows.push(handle.clone());
+            let mut response = Map::new();
+            response.insert("handle".to_string(), Value::String(handle));
+            response.insert("type".to_string(), Value::String("tab".to_string()));
+            Ok(Value::Object(response))
+        }
+        ("GET", ["window", "rect"]) => Ok(session.window_rect.clone()),
+        ("POST", ["window", "rect"]) => {
+            let rect = session.window_rect.as_object_mut().expect("window rect is an object");
+            for (k, v) in object(body)? {
+                if !v.is_null() {
+                    rect.insert(k.clone(), v.clone());
+                }
+            }
+            Ok(session.window_rect.clone())
+        }
+        ("POST", ["window", "maximize"]) | ("POST", ["window", "minimize"])
+        | ("POST", ["window", "fullscreen"]) => Ok(session.window_rect.clone()),
+        ("POST", ["frame"]) => {
+            match object(body)?.get("id") {
+                None | Some(Value::Null) => session.frame_path.clear(),
+                Some(frame) => session.frame_path.push(frame.clone()),
+            }
+            Ok(Value::Null)
+        }
+        ("POST", ["frame", "parent"]) => {
+            session.frame_path.pop();
+            Ok(Value::Null)
+        }
+        ("GET", ["element", "active"]) => state.active_element.clone()
+            .map(|e| element_reference(&e))
+            .ok_or_else(|| no_such_element("active element")),
+        ("POST", ["element"]) => find_element(state, None, body),
+        ("POST", ["elements"]) => find_elements(state, None, body),
+        (_, ["element", element, element_rest @ ..]) =>
+            element_command(state, method, element, element_rest, body),
+        ("POST", ["execute", "sync"]) | ("POST", ["execute", "async"]) => {
+            string_field(body, "script")?;
+            Ok(Value::Null)
+        }
+        ("GET", ["cookie"]) => Ok(Value::from(session.cookies.clone())),
+        ("GET", ["cookie", name]) => session.cookies.iter()
+            .find(|c| c.get("name").and_then(Value::as_str) == Some(*name))
+            .cloned()
+            .ok_or_else(|| MockResponse::error("no such cookie", &format!("No cookie named {}", name))),
+        ("POST", ["cookie"]) => {
+            let cookie = object(body)?.get("cookie")
+                .filter(|c| c.get("name").is_some() && c.get("value").is_some())
+                .cloned()
+                .ok_or_else(|| invalid_argument("Expected a cookie object with name and value"))?;
+            let name = cookie.get("name").cloned();
+            session.cookies.retain(|c| c.get("name").cloned() != name);
+            session.cookies.push(cookie);
+            Ok(Value::Null)
+        }
+        ("DELETE", ["cookie", name]) => {
+            session.cookies.retain(|c| c.get("name").and_then(Value::as_str) != Some(*name));
+            Ok(Value::Null)
+        }
+        ("DELETE", ["cookie"]) => {
+            session.cookies.clear();
+            Ok(Value::Null)
+        }
+        ("POST", ["actions"]) | ("DELETE", ["actions"]) => Ok(Value::Null),
+        ("POST", ["alert", "dismiss"]) | ("POST", ["alert", "accept"]) => {
+            session.alert.take().ok_or_else(no_such_alert)?;
+            session.alert_input = None;
+            Ok(Value::Null)
+        }
+        ("GET", ["alert", "text"]) => session.alert.clone()
+            .map(Value::String)
+            .ok_or_else(no_such_alert),
+        ("POST", ["alert", "text"]) => {
+            let text = string_field(body, "text")?;
+            session.alert.as_ref().ok_or_else(no_such_alert)?;
+            session.alert_input = Some(text);
+            Ok(Value::Null)
+        }
+        ("GET", ["screenshot"]) => Ok(Value::String(SCREENSHOT.to_string())),
+        ("POST", ["permissions"]) => {
+            let name = body.pointer("/descriptor/name")
+                .and_then(Value::as_str)
+                .ok_or_else(|| invalid_argument("Expected a permission descriptor with a name"))?;
+            let state = string_field(body, "state")?;
+            if !["granted", "denied", "prompt"].contains(&state.as_str()) {
+                return Err(invalid_argument(&format!("Unknown permission state {}", state)));
+            }
+            session.permissions.insert(name.to_string(), Value::String(state));
+            Ok(Value::Null)
+        }
+        ("POST", ["se", "log"]) | ("POST", ["log"]) => match string_field(body, "type")?.as_str() {
+            "browser" => Ok(Value::from(session.browser_log.drain(..).collect::<Vec<_>>())),
+            _ => Ok(Value::from(Vec::<Value>::new())),
+        },
+        ("POST", ["webauthn", "authenticator"]) => {
+            let options = object(body)?.clone();
+            let authenticator = state.next_id("authenticator");
+            let session = state.sessions.get_mut(id).ok_or_else(|| invalid_session(id))?;
+            session.authenticators.insert(authenticator.clone(), MockAuthenticator { options, credentials: Vec::new() });
+            Ok(Value::String(authenticator))
+        }
+        ("DELETE", ["webauthn", "authenticator", authenticator]) => session.authenticators.remove(*authenticator)
+            .map(|_| Value::Null)
+            .ok_or_else(|| no_such_authenticator(authenticator)),
+        (_, ["webauthn", "authenticator", authenticator, authenticator_rest @ ..]) => {
+            let authenticator = session.authenticators.get_mut(*authenticator)
+                .ok_or_else(|| no_such_authenticator(authenticator))?;
+            authenticator_command(authenticator, method, authenticator_rest, body)
+        }
+        _ => Err(unknown_command(method, &format!("session/{}/{}", id, rest.join("/")))),
+    }
+}
+
+fn authenticator_command(authenticator: &mut MockAuthenticator, method: &str, rest: &[&str], body: &Value) -> Outcome {
+    match (method, rest) {
+        ("POST", ["credential"]) => {
+            let id = string_field(body, "credentialId")?;
+            authenticator.credentials.retain(|c| c.get("credentialId").and_then(Value::as_str) != Some(id.as_str()));
+            authenticator.credentials.push(body.clone());
+            Ok(Value::Null)
+        }
+        ("GET", ["credentials"]) => Ok(Value::from(authenticator.credentials.clone())),
+        ("DELETE", ["credentials"]) => {
+            authenticator.credentials.clear();
+            Ok(Value::Null)
+        }
+        ("DELETE", ["credentials", credential]) => {
+            let count = authenticator.credentials.len();
+            authenticator.credentials.retain(|c| c.get("credentialId").and_then(Value::as_str) != Some(*credential));
+            if authenticator.credentials.len() == count {
+                return Err(invalid_argument(&format!("No credential {}", credential)));
+            }
+            Ok(Value::Null)
+        }
+        ("POST", ["uv"]) => {
+            let verified = object(body)?.get("isUserVerified")
+                .and_then(Value::as_bool)
+                .ok_or_else(|| invalid_argument("Expected boolean field isUserVerified"))?;
+            authenticator.options.insert("isUserVerified".to_string(), Value::Bool(verified));
+            Ok(Value::Null)
+        }
+        _ => Err(unknown_command(method, &format!("webauthn/authenticator/.../{}", rest.join("/")))),
+    }
+}
+
+fn element_command(state: &mut MockState, method: &str, id: &str, rest: &[&str], body: &Value) -> Outcome {
+    if state.stale.contains(id) {
+        return Err(MockResponse::error("stale element reference", &format!("Element {} is detached", id)));
+    }
+    if !state.elements.contains_key(id) {
+        return Err(no_such_element(id));
+    }
+    match (method, rest) {
+        ("POST", ["element"]) => return find_element(state, Some(id), body),
+        ("POST", ["elements"]) => return find_elements(state, Some(id), body),
+        _ => {}
+    }
+
+    let element = state.elements.get_mut(id).expect("element exists");
+    match (method, rest) {
+        ("GET", ["selected"]) => Ok(Value::Bool(element.selected)),
+        ("GET", ["enabled"]) => Ok(Value::Bool(element.enabled)),
+        ("GET", ["displayed"]) => Ok(Value::Bool(true)),
+        ("GET", ["attribute", name]) => Ok(element.attributes.get(*name).cloned().unwrap_or(Value::Null)),
+        ("GET", ["property", name]) => Ok(element.properties.get(*name).cloned().unwrap_or(Value::Null)),
+        ("GET", ["css", name]) => Ok(element.css.get(*name).cloned()
+            .unwrap_or_else(|| Value::String(String::new()))),
+        ("GET", ["text"]) => Ok(Value::String(element.text.clone())),
+        ("GET", ["name"]) => Ok(Value::String(element.tag_name.clone())),
+        ("GET", ["rect"]) => Ok(element.rect.clone()),
+        ("GET", ["screenshot"]) => Ok(Value::String(SCREENSHOT.to_string())),
+        ("POST", ["click"]) => {
+            let toggles = match element.attributes.get("type").and_then(Value::as_str) {
+                Some("checkbox") => true,
+                Some("radio") => !element.selected,
+                _ => false,
+            };
+            if toggles {
+                element.selected = !element.selected;
+            }
+            state.active_element = Some(id.to_string());
+            Ok(Value::Null)
+        }
+        ("POST", ["clear"]) => {
+            element.properties.insert("value".to_string(), Value::String(String::new()));
+            Ok(Value::Null)
+        }
+        ("POST", ["value"]) => {
+            let text = string_field(body, "text")?;
+            let value = element.properties.get("value")
+                .and_then(Value::as_str)
+                .unwrap_or("")
+                .to_string();
+            element.properties.insert("value".to_string(), Value::String(value + &text));
+            state.active_element = Some(id.to_string());
+            Ok(Value::Null)
+        }
+        _ => Err(unknown_command(method, &format!("element/{}/{}", id, rest.join("/")))),
+    }
+}
+
+fn find_element(state: &MockState, parent: Option<&str>, body: &Value) -> Outcome {
+    let (using, value) = locator(body)?;
+    state.find(parent, &using, &value)
+        .first()
+        .map(|e| element_reference(e))
+        .ok_or_else(|| no_such_element(&format!("{}={}", using, value)))
+}
+
+fn find_elements(state: &MockState, parent: Option<&str>, body: &Value) -> Outcome {
+    let (using, value) = locator(body)?;
+    Ok(Value::Array(state.find(parent, &using, &value)
+        .iter()
+        .map(|e| element_reference(e))
+        .collect()))
+}
+
+fn locator(body: &Value) -> Result<(String, String), MockResponse> {
+    Ok((string_field(body, "using")?, string_field(body, "value")?))
+}
+
+fn element_reference(id: &str) -> Value {
+    let mut reference = Map::new();
+    reference.insert(WEB_ELEMENT_IDENTIFIER.to_string(), Value::String(id.to_string()));
+    Value::Object(reference)
+}
+
+fn object(body: &Value) -> Result<&Map<String, Value>, MockResponse> {
+    body.as_object().ok_or_else(|| invalid_argument("Expected a JSON object body"))
+}
+
+fn string_field(body: &Value, name: &str) -> Result<String, MockResponse> {
+    object(body)?.get(name)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| invalid_argument(&format!("Expected string field {}", name)))
+}
+
+fn invalid_argument(message: &str) -> MockResponse {
+    MockResponse::error("invalid argument", message)
+}
+
+fn invalid_session(id: &str) -> MockResponse {
+    MockResponse::error("invalid session id", &format!("No active session {}", id))
+}
+
+fn no_such_element(what: &str) -> MockResponse {
+    MockResponse::error("no such element", &format!("Unable to locate {}", what))
+}
+
+fn no_such_window() -> MockResponse {
+    MockResponse::error("no such window", "No such window")
+}
+
+fn no_such_authenticator(id: &str) -> MockResponse {
+    invalid_argument(&format!("No virtual authenticator {}", id))
+}
+
+fn no_such_alert() -> MockResponse {
+    MockResponse::error("no such alert", "No alert is open")
+}
+
+fn unknown_command(method: &str, path: &str) -> MockResponse {
+    MockResponse::error("unknown command", &format!("{} {} is not supported", method, path))
+}
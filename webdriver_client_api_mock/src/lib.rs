@@ -0,0 +1,373 @@
+extern crate log;
+extern crate serde_json;
+extern crate tiny_http;
+extern crate webdriver_client_api as wda;
+
+mod handler;
+pub mod state;
+
+use log::*;
+use serde_json::Map;
+use serde_json::Value;
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use tiny_http::Header;
+use tiny_http::Response;
+use tiny_http::Server;
+
+use wda::jsonwire;
+use wda::WEB_ELEMENT_IDENTIFIER;
+
+use crate::state::MockElement;
+use crate::state::MockPage;
+use crate::state::MockState;
+
+/// Request as received by the mock server.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Path without the leading slash, e.g. `session/session-1/url`.
+    pub path: String,
+    /// Parsed JSON body, `Value::Null` when the request had none.
+    pub body: Value,
+    pub headers: Vec<(String, String)>,
+}
+
+impl RecordedRequest {
+    /// Value of the first header named `name`, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Response served by the mock, either computed from its state or enqueued by a test.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MockResponse {
+    pub status: u16,
+    pub value: Value,
+    /// How long the server waits before answering.
+    pub delay: Duration,
+}
+
+impl MockResponse {
+    pub fn value(value: Value) -> Self {
+        MockResponse { status: 200, value, delay: Duration::from_secs(0) }
+    }
+
+    pub fn delayed(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn error(error: &str, message: &str) -> Self {
+        MockResponse::error_with_data(error, message, Map::new())
+    }
+
+    pub fn error_with_data(error: &str, message: &str, data: Map<String, Value>) -> Self {
+        let mut value = Map::new();
+        value.insert("error".to_string(), Value::String(error.to_string()));
+        value.insert("message".to_string(), Value::String(message.to_string()));
+        value.insert("stacktrace".to_string(), Value::String(String::new()));
+        if !data.is_empty() {
+            value.insert("data".to_string(), Value::Object(data));
+        }
+        MockResponse { status: status_code(error), value: Value::Object(value), delay: Duration::from_secs(0) }
+    }
+}
+
+/// HTTP status the W3C specification assigns to an error code.
+pub fn status_code(error: &str) -> u16 {
+    match error {
+        "element click intercepted" | "element not interactable" | "insecure certificate"
+        | "invalid argument" | "invalid cookie domain" | "invalid element state"
+        | "invalid selector" => 400,
+        "invalid session id" | "no such alert" | "no such cookie" | "no such element"
+        | "no such frame" | "no such shadow root" | "no such window" | "stale element reference"
+        | "detached shadow root" | "unknown command" => 404,
+        "unknown method" => 405,
+        _ => 500,
+    }
+}
+
+struct Canned {
+    method: String,
+    pattern: String,
+    response: MockResponse,
+}
+
+struct Shared {
+    state: MockState,
+    canned: VecDeque<Canned>,
+    requests: Vec<RecordedRequest>,
+    json_wire: bool,
+}
+
+/// WebDriver server listening on localhost and answering W3C commands from in-memory state.
+///
+/// The server stops when dropped.
+pub struct MockWebDriver {
+    url: String,
+    server: Arc<Server>,
+    shared: Arc<Mutex<Shared>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl MockWebDriver {
+    pub fn start() -> Self {
+        let server = Arc::new(Server::http("127.0.0.1:0").expect("Cannot bind mock WebDriver server"));
+        let url = format!("http://{}", server.server_addr());
+        let shared = Arc::new(Mutex::new(Shared {
+            state: MockState::new(),
+            canned: VecDeque::new(),
+            requests: Vec::new(),
+            json_wire: false,
+        }));
+
+        let worker = {
+            let server = server.clone();
+            let shared = shared.clone();
+            thread::spawn(move || {
+                while let Ok(request) = server.recv() {
+                    serve(&shared, request);
+                }
+            })
+        };
+        info!("Mock WebDriver listening on {}", url);
+        MockWebDriver { url, server, shared, worker: Some(worker) }
+    }
+
+    pub fn url(&self) -> &str {
+        self.url.as_str()
+    }
+
+    /// Locks the state for scripting or inspection; commands wait until the guard is dropped.
+    pub fn state(&self) -> StateGuard<'_> {
+        StateGuard { guard: self.lock() }
+    }
+
+    /// Serves `response` to the next request matching `method` and `pattern` instead of the state.
+    ///
+    /// `pattern` is a path without the leading slash where `*` matches one segment,
+    /// e.g. `session/*/element`. Responses for the same endpoint are served in order.
+    pub fn enqueue(&self, method: &str, pattern: &str, response: MockResponse) {
+        self.lock().canned.push_back(Canned {
+            method: method.to_string(),
+            pattern: pattern.trim_matches('/').to_string(),
+            response,
+        });
+    }
+
+    pub fn enqueue_error(&self, method: &str, pattern: &str, error: &str, message: &str) {
+        self.enqueue(method, pattern, MockResponse::error(error, message));
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.lock().requests.clone()
+    }
+
+    pub fn last_request(&self) -> Option<RecordedRequest> {
+        self.lock().requests.last().cloned()
+    }
+
+    pub fn clear_requests(&self) {
+        self.lock().requests.clear();
+    }
+
+    pub fn add_page(&self, url: &str, title: &str, source: &str) {
+        self.lock().state.pages.insert(url.to_string(), MockPage {
+            title: title.to_string(),
+            source: source.to_string(),
+        });
+    }
+
+    /// Registers an element found from the document root by `using`/`value`, returning its id.
+    pub fn add_element(&self, using: &str, value: &str, element: MockElement) -> String {
+        self.lock().state.add_element(None, using, value, element)
+    }
+
+    pub fn add_child_element(&self, parent: &str, using: &str, value: &str, element: MockElement) -> String {
+        self.lock().state.add_element(Some(parent), using, value, element)
+    }
+
+    pub fn remove_element(&self, id: &str) {
+        self.lock().state.remove_element(id)
+    }
+
+    /// Answers like a legacy JSON Wire Protocol server: `{status, sessionId, value}` envelopes
+    /// with numeric statuses and `ELEMENT` keys in element references.
+    pub fn set_json_wire(&self, json_wire: bool) {
+        self.lock().json_wire = json_wire;
+    }
+
+    /// Opens a user prompt in every active session.
+    pub fn open_alert(&self, text: &str) {
+        for session in self.lock().state.sessions.values_mut() {
+            session.alert = Some(text.to_string());
+        }
+    }
+
+    /// Logs to the browser console of every active session, as read by the log endpoints.
+    pub fn log_to_console(&self, level: &str, message: &str) {
+        let mut entry = Map::new();
+        entry.insert("level".to_string(), Value::String(level.to_string()));
+        entry.insert("message".to_string(), Value::String(message.to_string()));
+        entry.insert("source".to_string(), Value::String("console-api".to_string()));
+        entry.insert("timestamp".to_string(), Value::from(1_700_000_000_000u64));
+        for session in self.lock().state.sessions.values_mut() {
+            session.browser_log.push(Value::Object(entry.clone()));
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Shared> {
+        self.shared.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for MockWebDriver {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Exclusive access to the `MockState` of a running server.
+pub struct StateGuard<'a> {
+    guard: MutexGuard<'a, Shared>,
+}
+
+impl<'a> std::ops::Deref for StateGuard<'a> {
+    type Target = MockState;
+
+    fn deref(&self) -> &MockState {
+        &self.guard.state
+    }
+}
+
+impl<'a> std::ops::DerefMut for StateGuard<'a> {
+    fn deref_mut(&mut self) -> &mut MockState {
+        &mut self.guard.state
+    }
+}
+
+fn serve(shared: &Mutex<Shared>, mut request: tiny_http::Request) {
+    let method = request.method().as_str().to_uppercase();
+    let headers = request.headers().iter()
+        .map(|h| (h.field.as_str().to_string(), h.value.as_str().to_string()))
+        .collect::<Vec<_>>();
+    let path = request.url().split('?').next().unwrap_or("").trim_matches('/').to_string();
+    let mut content = String::new();
+    let mut json_wire = false;
+    let response = match request.as_reader().read_to_string(&mut content) {
+        Err(e) => MockResponse::error("invalid argument", &format!("Cannot read body: {}", e)),
+        Ok(_) => match parse_body(&content) {
+            Err(e) => MockResponse::error("invalid argument", &format!("Body is not JSON: {}", e)),
+            Ok(body) => {
+                let mut shared = shared.lock().unwrap_or_else(|e| e.into_inner());
+                json_wire = shared.json_wire;
+                debug!("Mock received {} /{} {}", method, path, body);
+                shared.requests.push(RecordedRequest {
+                    method: method.clone(),
+                    path: path.clone(),
+                    body: body.clone(),
+                    headers,
+                });
+                let canned = shared.canned.iter()
+                    .position(|c| c.method == method && matches_pattern(&c.pattern, &path));
+                match canned {
+                    Some(i) => shared.canned.remove(i).expect("canned response exists").response,
+                    None => match handler::handle(&mut shared.state, &method, &path, &body) {
+                        Ok(value) => MockResponse::value(value),
+                        Err(error) => error,
+                    }
+                }
+            }
+        }
+    };
+
+    if response.delay > Duration::from_secs(0) {
+        thread::sleep(response.delay);
+    }
+    let envelope = if json_wire {
+        json_wire_envelope(&path, response.value)
+    } else {
+        let mut envelope = Map::new();
+        envelope.insert("value".to_string(), response.value);
+        Value::Object(envelope)
+    };
+    let reply = Response::from_string(envelope.to_string())
+        .with_status_code(response.status)
+        .with_header(Header::from_bytes(&b"Content-Type"[..], &b"application/json; charset=utf-8"[..])
+            .expect("valid header"));
+    if let Err(e) = request.respond(reply) {
+        warn!("Mock cannot send response: {}", e);
+    }
+}
+
+fn json_wire_envelope(path: &str, mut value: Value) -> Value {
+    let mut envelope = Map::new();
+    let error = value.get("error").and_then(Value::as_str).map(str::to_string);
+    match error {
+        Some(error) => {
+            envelope.insert("status".to_string(), Value::from(jsonwire::status_code(&error)));
+            if let Some(object) = value.as_object_mut() {
+                object.remove("error");
+            }
+        }
+        None => {
+            envelope.insert("status".to_string(), Value::from(0));
+        }
+    }
+
+    let mut segments = path.split('/');
+    let session_id = match (segments.next(), segments.next()) {
+        (Some("session"), Some(id)) => Some(Value::String(id.to_string())),
+        _ => value.get_mut("sessionId").map(Value::take),
+    };
+    if path == "session" {
+        if let Some(capabilities) = value.get_mut("capabilities").map(Value::take) {
+            value = capabilities;
+        }
+    }
+    envelope.insert("sessionId".to_string(), session_id.unwrap_or(Value::Null));
+    envelope.insert("value".to_string(), legacy_element_keys(value));
+    Value::Object(envelope)
+}
+
+fn legacy_element_keys(value: Value) -> Value {
+    match value {
+        Value::Array(items) => Value::Array(items.into_iter().map(legacy_element_keys).collect()),
+        Value::Object(object) => Value::Object(object.into_iter()
+            .map(|(k, v)| match k.as_str() {
+                WEB_ELEMENT_IDENTIFIER => (jsonwire::ELEMENT_IDENTIFIER.to_string(), v),
+                _ => (k, legacy_element_keys(v)),
+            })
+            .collect()),
+        other => other,
+    }
+}
+
+fn parse_body(content: &str) -> serde_json::Result<Value> {
+    if content.trim().is_empty() {
+        Ok(Value::Null)
+    } else {
+        serde_json::from_str(content)
+    }
+}
+
+fn matches_pattern(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    let path: Vec<&str> = path.split('/').collect();
+    pattern.len() == path.len()
+        && pattern.iter().zip(path.iter()).all(|(p, s)| *p == "*" || p == s)
+}
@@ -0,0 +1,220 @@
+use serde_json::Map;
+use serde_json::Value;
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+/// Element of the mocked page, found by the locators registered for it.
+#[derive(Clone, Debug)]
+pub struct MockElement {
+    pub tag_name: String,
+    pub text: String,
+    pub attributes: Map<String, Value>,
+    pub properties: Map<String, Value>,
+    pub css: Map<String, Value>,
+    pub rect: Value,
+    pub selected: bool,
+    pub enabled: bool,
+}
+
+impl MockElement {
+    pub fn new(tag_name: &str) -> Self {
+        MockElement {
+            tag_name: tag_name.to_string(),
+            text: String::new(),
+            attributes: Map::new(),
+            properties: Map::new(),
+            css: Map::new(),
+            rect: rect(0, 0, 0, 0),
+            selected: false,
+            enabled: true,
+        }
+    }
+
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = text.to_string();
+        self
+    }
+
+    pub fn attribute(mut self, name: &str, value: &str) -> Self {
+        self.attributes.insert(name.to_string(), Value::String(value.to_string()));
+        self
+    }
+
+    pub fn property(mut self, name: &str, value: Value) -> Self {
+        self.properties.insert(name.to_string(), value);
+        self
+    }
+
+    pub fn css(mut self, name: &str, value: &str) -> Self {
+        self.css.insert(name.to_string(), Value::String(value.to_string()));
+        self
+    }
+
+    pub fn rect(mut self, x: i32, y: i32, width: i32, height: i32) -> Self {
+        self.rect = rect(x, y, width, height);
+        self
+    }
+
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+}
+
+/// Binds a `using`/`value` pair, searched from `parent` or the document, to an element.
+#[derive(Clone, Debug)]
+pub struct MockLocator {
+    pub parent: Option<String>,
+    pub using: String,
+    pub value: String,
+    pub element: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MockPage {
+    pub title: String,
+    pub source: String,
+}
+
+/// WebAuthn virtual authenticator: its options as sent, and the credentials added to it.
+#[derive(Clone, Debug, Default)]
+pub struct MockAuthenticator {
+    pub options: Map<String, Value>,
+    pub credentials: Vec<Value>,
+}
+
+#[derive(Clone, Debug)]
+pub struct MockSession {
+    pub capabilities: Map<String, Value>,
+    pub timeouts: Map<String, Value>,
+    pub history: Vec<String>,
+    pub history_index: usize,
+    pub windows: Vec<String>,
+    pub current_window: Option<String>,
+    pub window_rect: Value,
+    pub frame_path: Vec<Value>,
+    pub cookies: Vec<Value>,
+    pub alert: Option<String>,
+    pub alert_input: Option<String>,
+    /// Permission states by descriptor name.
+    pub permissions: Map<String, Value>,
+    pub authenticators: HashMap<String, MockAuthenticator>,
+    /// Browser log entries not read yet through the log endpoints.
+    pub browser_log: Vec<Value>,
+}
+
+impl MockSession {
+    pub(crate) fn new(capabilities: Map<String, Value>, window: String) -> Self {
+        let mut timeouts = Map::new();
+        timeouts.insert("script".to_string(), Value::from(30000));
+        timeouts.insert("pageLoad".to_string(), Value::from(300000));
+        timeouts.insert("implicit".to_string(), Value::from(0));
+        MockSession {
+            capabilities,
+            timeouts,
+            history: vec!["about:blank".to_string()],
+            history_index: 0,
+            windows: vec![window.clone()],
+            current_window: Some(window),
+            window_rect: rect(0, 0, 1280, 720),
+            frame_path: Vec::new(),
+            cookies: Vec::new(),
+            alert: None,
+            alert_input: None,
+            permissions: Map::new(),
+            authenticators: HashMap::new(),
+            browser_log: Vec::new(),
+        }
+    }
+
+    pub fn current_url(&self) -> &str {
+        self.history[self.history_index].as_str()
+    }
+}
+
+/// Scriptable in-memory state shared by all sessions of a mock server.
+#[derive(Debug)]
+pub struct MockState {
+    pub ready: bool,
+    pub sessions: HashMap<String, MockSession>,
+    pub pages: HashMap<String, MockPage>,
+    pub elements: HashMap<String, MockElement>,
+    pub locators: Vec<MockLocator>,
+    pub stale: HashSet<String>,
+    pub active_element: Option<String>,
+    next_id: u64,
+}
+
+impl MockState {
+    pub(crate) fn new() -> Self {
+        MockState {
+            ready: true,
+            sessions: HashMap::new(),
+            pages: HashMap::new(),
+            elements: HashMap::new(),
+            locators: Vec::new(),
+            stale: HashSet::new(),
+            active_element: None,
+            next_id: 0,
+        }
+    }
+
+    pub fn next_id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{}-{}", prefix, self.next_id)
+    }
+
+    pub fn add_element(&mut self, parent: Option<&str>, using: &str, value: &str, element: MockElement) -> String {
+        let id = self.next_id("element");
+        self.elements.insert(id.clone(), element);
+        self.add_locator(parent, using, value, &id);
+        id
+    }
+
+    pub fn add_locator(&mut self, parent: Option<&str>, using: &str, value: &str, element: &str) {
+        self.locators.push(MockLocator {
+            parent: parent.map(str::to_string),
+            using: using.to_string(),
+            value: value.to_string(),
+            element: element.to_string(),
+        });
+    }
+
+    /// Detaches the element and its descendants, so further commands on them fail with a
+    /// stale element reference.
+    pub fn remove_element(&mut self, id: &str) {
+        if self.elements.remove(id).is_some() {
+            self.stale.insert(id.to_string());
+        }
+        let children: Vec<String> = self.locators.iter()
+            .filter(|l| l.parent.as_deref() == Some(id))
+            .map(|l| l.element.clone())
+            .collect();
+        self.locators.retain(|l| l.element != id && l.parent.as_deref() != Some(id));
+        for child in children {
+            self.remove_element(&child);
+        }
+    }
+
+    pub fn find(&self, parent: Option<&str>, using: &str, value: &str) -> Vec<String> {
+        self.locators.iter()
+            .filter(|l| l.parent.as_deref() == parent && l.using == using && l.value == value)
+            .map(|l| l.element.clone())
+            .collect()
+    }
+}
+
+pub fn rect(x: i32, y: i32, width: i32, height: i32) -> Value {
+    let mut map = Map::new();
+    map.insert("x".to_string(), Value::from(x));
+    map.insert("y".to_string(), Value::from(y));
+    map.insert("width".to_string(), Value::from(width));
+    map.insert("height".to_string(), Value::from(height));
+    Value::Object(map)
+}
@@ -0,0 +1,99 @@
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_simple_impl as simple_impl;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+
+mod common;
+
+use mock::MockWebDriver;
+use serde_json::json;
+use simplehttp::HttpClient;
+use wda::alert::AlertExecutor;
+use wda::alert::AlertPolicy;
+use wda::traits::HttpExecutor;
+use wda::traits::SessionHandle;
+
+use std::thread;
+use std::time::Duration;
+
+fn connect<H: HttpExecutor>(http: H, prompts: &str) -> SessionHandle<H> {
+    common::connect_with(http, json!({"capabilities": {"alwaysMatch": {"unhandledPromptBehavior": prompts}}}))
+}
+
+#[test]
+fn alert_handles_read_and_answer_prompts() {
+    let mock = MockWebDriver::start();
+    let session = connect(HttpClient::new(mock.url().to_string()), "dismiss and notify");
+    assert_eq!(session.alert().err().unwrap().error, "no such alert");
+
+    mock.open_alert("Your name?");
+    let alert = session.alert().unwrap();
+    assert_eq!(alert.text(), "Your name?");
+    alert.send_keys("Ada").unwrap();
+    assert_eq!(mock.state().sessions[session.get_session_id()].alert_input.as_deref(), Some("Ada"));
+    alert.accept().unwrap();
+    assert!(mock.last_request().unwrap().path.ends_with("/alert/accept"));
+    assert_eq!(session.alert().err().unwrap().error, "no such alert");
+}
+
+#[test]
+fn wait_for_alert_polls_until_timeout() {
+    let mock = MockWebDriver::start();
+    let session = connect(HttpClient::new(mock.url().to_string()), "dismiss and notify");
+    let error = session.wait_for_alert(Duration::from_millis(250)).err().unwrap();
+    assert_eq!(error.error, "no such alert");
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            thread::sleep(Duration::from_millis(150));
+            mock.open_alert("Saved");
+        });
+        let alert = session.wait_for_alert(Duration::from_secs(5)).unwrap();
+        assert_eq!(alert.text(), "Saved");
+        alert.dismiss().unwrap();
+    });
+}
+
+#[test]
+fn unexpected_alert_errors_carry_the_text() {
+    let mock = MockWebDriver::start();
+    let session = connect(HttpClient::new(mock.url().to_string()), "dismiss and notify");
+    mock.open_alert("Leave page?");
+
+    let error = session.get_title().unwrap_err();
+    assert_eq!(error.alert_text(), Some("Leave page?"));
+    assert_eq!(session.get_title().unwrap(), "");
+    assert_eq!(wda::structs::WebDriverError::new("unknown error", "").alert_text(), None);
+}
+
+#[test]
+fn policy_answers_prompts_and_retries() {
+    let mock = MockWebDriver::start();
+    let http = AlertExecutor::new(Box::new(HttpClient::new(mock.url().to_string())), AlertPolicy::accept());
+    let session = connect(http, "ignore");
+    mock.open_alert("Leave page?");
+
+    session.navigate_to("https://example.com/").unwrap();
+
+    let paths: Vec<String> = mock.requests().iter().map(|r| r.path.clone()).collect();
+    let id = session.get_session_id();
+    assert!(paths.ends_with(&[
+        format!("session/{}/url", id),
+        format!("session/{}/alert/accept", id),
+        format!("session/{}/url", id),
+    ]));
+    assert_eq!(session.get_current_url().unwrap(), "https://example.com/");
+}
+
+#[test]
+fn policy_tolerates_prompts_closed_by_the_driver() {
+    let mock = MockWebDriver::start();
+    let policy = AlertPolicy::dismiss().max_retries(1);
+    let http = AlertExecutor::new(Box::new(HttpClient::new(mock.url().to_string())), policy);
+    let session = connect(http, "dismiss and notify");
+    mock.open_alert("Leave page?");
+
+    assert_eq!(session.get_title().unwrap(), "");
+    assert!(mock.requests().iter().any(|r| r.path.ends_with("/alert/dismiss")));
+}
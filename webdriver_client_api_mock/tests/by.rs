@@ -0,0 +1,55 @@
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_simple_impl as simple_impl;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+
+mod common;
+
+use mock::state::MockElement;
+use mock::MockWebDriver;
+use serde_json::json;
+use wda::by::By;
+use wda::structs::WebDriverLocator;
+
+use std::collections::HashSet;
+
+#[test]
+fn non_native_strategies_become_css() {
+    assert_eq!(By::id("login"), By::css("#login"));
+    assert_eq!(By::id("1st item").value(), "#\\31 st\\ item");
+    assert_eq!(By::class_name("btn-primary").value(), ".btn-primary");
+    assert_eq!(By::class_name("-").value(), ".\\-");
+    assert_eq!(By::name("user[email]").value(), "*[name=\"user[email]\"]");
+    assert_eq!(By::test_id("say \"hi\"").value(), "*[data-testid=\"say \\\"hi\\\"\"]");
+    assert_eq!(By::xpath("//a").using(), "xpath");
+    assert_eq!(By::custom("accessibility id", "Submit").using(), "accessibility id");
+}
+
+#[test]
+fn locators_are_values() {
+    let by = By::from((WebDriverLocator::Tag, "input"));
+    assert_eq!(by.to_string(), "tag name \"input\"");
+    assert_eq!(By::css("a[href]").to_string(), "css selector \"a[href]\"");
+
+    let set: HashSet<By> = vec![By::id("a"), By::css("#a"), by.clone()].into_iter().collect();
+    assert_eq!(set.len(), 2);
+    assert!(set.contains(&by));
+}
+
+#[test]
+fn sessions_and_elements_find_by() {
+    let mock = MockWebDriver::start();
+    let form = mock.add_element("css selector", "#signup", MockElement::new("form"));
+    let email = mock.add_child_element(&form, "css selector", "*[name=\"email\"]", MockElement::new("input"));
+    let session = common::connect(&mock);
+
+    let found = session.find_element_by(&By::id("signup")).unwrap();
+    assert_eq!(found.get_reference_id(), form);
+    assert_eq!(mock.last_request().unwrap().body, json!({"using": "css selector", "value": "#signup"}));
+
+    let inputs = found.find_elements_by(&By::name("email")).unwrap();
+    assert_eq!(inputs.len(), 1);
+    assert_eq!(inputs[0].get_reference_id(), email);
+    assert_eq!(found.find_element_by(&By::name("email")).unwrap().get_reference_id(), email);
+}
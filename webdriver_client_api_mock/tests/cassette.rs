@@ -0,0 +1,106 @@
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_simple_impl as simple_impl;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+
+use mock::state::MockElement;
+use mock::MockWebDriver;
+use serde_json::json;
+use simple_impl::DefaultWebDriver;
+use simplehttp::HttpClient;
+use wda::by::By;
+use wda::cassette::RecordingExecutor;
+use wda::cassette::ReplayExecutor;
+use wda::cassette::ReplayMode;
+use wda::traits::HttpExecutor;
+use wda::traits::WebDriver;
+
+use std::fs;
+use std::path::Path;
+
+fn flow<H: HttpExecutor + 'static>(http: H) -> (String, String) {
+    let caps = json!({"capabilities": {}});
+    let session = DefaultWebDriver::new(Box::new(http))
+        .create_session(caps.as_object().unwrap().clone())
+        .unwrap();
+    session.navigate_to("https://example.com/").unwrap();
+    let title = session.get_title().unwrap();
+    let error = session.get_alert_text().unwrap_err().error;
+    session.delete_session().unwrap();
+    (title, error)
+}
+
+#[test]
+fn recorded_flow_replays_offline() {
+    let file = std::env::temp_dir().join(format!("wda-cassette-{}.json", std::process::id()));
+    let recorded = {
+        let mock = MockWebDriver::start();
+        mock.add_page("https://example.com/", "Example", "");
+        flow(RecordingExecutor::new(Box::new(HttpClient::new(mock.url().to_string())), &file))
+    };
+    assert_eq!(recorded, ("Example".to_string(), "no such alert".to_string()));
+
+    for mode in &[ReplayMode::InOrder, ReplayMode::Match] {
+        let replay = ReplayExecutor::from_file(&file, *mode).unwrap();
+        assert_eq!(flow(replay.clone()), recorded);
+        assert_eq!(replay.remaining(), 0);
+    }
+    fs::remove_file(&file).unwrap();
+}
+
+fn upload<H: HttpExecutor + 'static>(http: H, file: &Path) {
+    let caps = json!({"capabilities": {}});
+    let session = DefaultWebDriver::new(Box::new(http))
+        .create_session(caps.as_object().unwrap().clone())
+        .unwrap();
+    session.find_element_by(&By::css("input")).unwrap().upload_file(file).unwrap();
+}
+
+#[test]
+fn replay_answers_is_local_like_the_recording() {
+    let file = std::env::temp_dir().join(format!("wda-cassette-upload-{}.json", std::process::id()));
+    let path = std::env::temp_dir().join(format!("wda-cassette-upload-{}.txt", std::process::id()));
+    fs::write(&path, "a").unwrap();
+    let path = fs::canonicalize(path).unwrap();
+    {
+        let mock = MockWebDriver::start();
+        mock.add_element("css selector", "input", MockElement::new("input").property("type", json!("file")));
+        upload(RecordingExecutor::new(Box::new(HttpClient::new(mock.url().to_string())), &file), &path);
+    }
+
+    // Answering `false` would send the file through `se/file`, which is not on the tape
+    let replay = ReplayExecutor::from_file(&file, ReplayMode::InOrder).unwrap();
+    assert!(replay.is_local());
+    upload(replay.clone(), &path);
+    assert_eq!(replay.remaining(), 0);
+    fs::remove_file(&path).unwrap();
+    fs::remove_file(&file).unwrap();
+}
+
+#[test]
+#[should_panic(expected = "Unmatched request in cassette")]
+fn unmatched_request_panics() {
+    let replay = ReplayExecutor::new(Vec::new(), ReplayMode::Match);
+    let _: Result<String, _> = replay.get("status");
+}
+
+#[test]
+fn cassette_is_written_on_save_and_drop() {
+    let file = std::env::temp_dir().join(format!("wda-cassette-buffered-{}.json", std::process::id()));
+    let mock = MockWebDriver::start();
+    let recording = RecordingExecutor::new(Box::new(HttpClient::new(mock.url().to_string())), &file);
+    let clone = recording.clone();
+
+    let _: serde_json::Value = recording.get("status").unwrap();
+    assert!(!file.exists());
+    recording.save().unwrap();
+    assert_eq!(ReplayExecutor::from_file(&file, ReplayMode::InOrder).unwrap().remaining(), 1);
+
+    let _: serde_json::Value = clone.get("status").unwrap();
+    drop(recording);
+    assert_eq!(ReplayExecutor::from_file(&file, ReplayMode::InOrder).unwrap().remaining(), 1);
+    drop(clone);
+    assert_eq!(ReplayExecutor::from_file(&file, ReplayMode::InOrder).unwrap().remaining(), 2);
+    fs::remove_file(&file).unwrap();
+}
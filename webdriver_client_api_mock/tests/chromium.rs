@@ -0,0 +1,99 @@
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_simple_impl as simple_impl;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+
+mod common;
+
+use mock::MockResponse;
+use mock::MockWebDriver;
+use serde_json::json;
+use serde_json::Value;
+use simple_impl::DefaultSession;
+use simplehttp::HttpClient;
+use wda::chromium::ChromiumSession;
+use wda::chromium::NetworkConditions;
+use wda::traits::Session;
+
+fn connect(mock: &MockWebDriver, browser: &str) -> DefaultSession<HttpClient> {
+    common::connect_default(mock, json!({"capabilities": {"alwaysMatch": {"browserName": browser}}}))
+}
+
+fn last_command(mock: &MockWebDriver, session: &DefaultSession<HttpClient>) -> (String, String, Value) {
+    let request = mock.last_request().unwrap();
+    let prefix = format!("session/{}/", session.get_session_id());
+    (request.method, request.path.trim_start_matches(&prefix).to_string(), request.body)
+}
+
+#[test]
+fn cdp_commands_use_the_vendor_prefix() {
+    let mock = MockWebDriver::start();
+    let chrome = connect(&mock, "chrome");
+    let edge = connect(&mock, "MicrosoftEdge");
+    assert_eq!(chrome.vendor_prefix(), "goog");
+    assert_eq!(edge.vendor_prefix(), "ms");
+
+    mock.enqueue("POST", "session/*/goog/cdp/execute", MockResponse::value(json!({"result": {"value": 2}})));
+    let result = chrome.execute_cdp("Runtime.evaluate", json!({"expression": "1 + 1"})).unwrap();
+    assert_eq!(result, json!({"result": {"value": 2}}));
+    assert_eq!(last_command(&mock, &chrome), ("POST".to_string(), "goog/cdp/execute".to_string(), json!({
+        "cmd": "Runtime.evaluate",
+        "params": {"expression": "1 + 1"},
+    })));
+
+    mock.enqueue("POST", "session/*/ms/cdp/execute", MockResponse::value(json!({})));
+    edge.set_timezone_override("Europe/Berlin").unwrap();
+    assert_eq!(last_command(&mock, &edge), ("POST".to_string(), "ms/cdp/execute".to_string(), json!({
+        "cmd": "Emulation.setTimezoneOverride",
+        "params": {"timezoneId": "Europe/Berlin"},
+    })));
+}
+
+#[test]
+fn network_conditions_are_shared_between_vendors() {
+    let mock = MockWebDriver::start();
+    let edge = connect(&mock, "msedge");
+    let conditions = NetworkConditions {
+        offline: false,
+        latency: 150,
+        download_throughput: 50_000,
+        upload_throughput: 20_000,
+    };
+
+    mock.enqueue("POST", "session/*/chromium/network_conditions", MockResponse::value(Value::Null));
+    edge.set_network_conditions(&conditions).unwrap();
+    assert_eq!(last_command(&mock, &edge), ("POST".to_string(), "chromium/network_conditions".to_string(), json!({
+        "network_conditions": {"offline": false, "latency": 150, "download_throughput": 50_000, "upload_throughput": 20_000},
+    })));
+
+    mock.enqueue("GET", "session/*/chromium/network_conditions", MockResponse::value(json!({
+        "latency": 150, "download_throughput": 50_000, "upload_throughput": 20_000,
+    })));
+    assert_eq!(edge.get_network_conditions().unwrap(), conditions);
+
+    mock.enqueue("DELETE", "session/*/chromium/network_conditions", MockResponse::value(Value::Null));
+    edge.delete_network_conditions().unwrap();
+    assert_eq!(last_command(&mock, &edge).0, "DELETE");
+}
+
+#[test]
+fn cast_commands_name_the_sink() {
+    let mock = MockWebDriver::start();
+    let chrome = connect(&mock, "chrome");
+    let edge = connect(&mock, "MicrosoftEdge");
+
+    mock.enqueue("GET", "session/*/goog/cast/get_sinks", MockResponse::value(json!([{"name": "Living Room", "id": "a1"}])));
+    assert_eq!(chrome.get_cast_sinks().unwrap()[0]["name"], json!("Living Room"));
+    assert_eq!(last_command(&mock, &chrome).1, "goog/cast/get_sinks");
+
+    mock.enqueue("POST", "session/*/ms/cast/start_tab_mirroring", MockResponse::value(Value::Null));
+    edge.start_tab_mirroring("Living Room").unwrap();
+    assert_eq!(last_command(&mock, &edge), ("POST".to_string(), "ms/cast/start_tab_mirroring".to_string(), json!({
+        "sinkName": "Living Room",
+    })));
+
+    mock.enqueue("POST", "session/*/goog/cast/stop_casting", MockResponse::value(Value::Null));
+    chrome.stop_casting("Living Room").unwrap();
+    assert_eq!(last_command(&mock, &chrome).1, "goog/cast/stop_casting");
+}
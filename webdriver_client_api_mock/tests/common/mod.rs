@@ -0,0 +1,29 @@
+// Each test crate uses only some of the fixtures
+#![allow(dead_code)]
+
+use mock::MockWebDriver;
+use serde_json::json;
+use serde_json::Value;
+use simple_impl::DefaultSession;
+use simple_impl::DefaultWebDriver;
+use simplehttp::HttpClient;
+use wda::traits::HttpExecutor;
+use wda::traits::SessionHandle;
+use wda::traits::WebDriver;
+
+/// Session on `mock` with empty capabilities.
+pub fn connect(mock: &MockWebDriver) -> SessionHandle<HttpClient> {
+    connect_with(HttpClient::new(mock.url().to_string()), json!({"capabilities": {}}))
+}
+
+/// Session created through `http` with the new session body `caps`.
+pub fn connect_with<H: HttpExecutor>(http: H, caps: Value) -> SessionHandle<H> {
+    let driver = DefaultWebDriver::new(Box::new(http));
+    driver.create_session(caps.as_object().unwrap().clone()).unwrap()
+}
+
+/// Session on `mock` kept as `DefaultSession` for the vendor extension traits.
+pub fn connect_default(mock: &MockWebDriver, caps: Value) -> DefaultSession<HttpClient> {
+    let driver = DefaultWebDriver::new(Box::new(HttpClient::new(mock.url().to_string())));
+    driver.create_default_session(caps.as_object().unwrap().clone()).unwrap()
+}
@@ -0,0 +1,75 @@
+extern crate base64;
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_simple_impl as simple_impl;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+
+mod common;
+
+use mock::MockResponse;
+use mock::MockWebDriver;
+use serde_json::json;
+use serde_json::Value;
+use simple_impl::DefaultSession;
+use simplehttp::HttpClient;
+use wda::firefox::FirefoxContext;
+use wda::firefox::FirefoxSession;
+
+use std::fs;
+
+fn connect(mock: &MockWebDriver) -> DefaultSession<HttpClient> {
+    common::connect_default(mock, json!({"capabilities": {"alwaysMatch": {"browserName": "firefox"}}}))
+}
+
+#[test]
+fn addons_are_sent_base64_encoded() {
+    let mock = MockWebDriver::start();
+    let session = connect(&mock);
+    let xpi = std::env::temp_dir().join(format!("wda-addon-{}.xpi", std::process::id()));
+    fs::write(&xpi, b"PK\x03\x04manifest").unwrap();
+
+    for &temporary in &[true, false] {
+        mock.enqueue("POST", "session/*/moz/addon/install", MockResponse::value(json!("helper@example.com")));
+        assert_eq!(session.install_addon(&xpi, temporary).unwrap(), "helper@example.com");
+        let request = mock.last_request().unwrap();
+        assert!(request.path.ends_with("/moz/addon/install"));
+        assert_eq!(request.body, json!({"addon": base64::encode(b"PK\x03\x04manifest"), "temporary": temporary}));
+    }
+    fs::remove_file(&xpi).unwrap();
+
+    let requests = mock.requests().len();
+    assert_eq!(session.install_addon(&xpi, true).unwrap_err().error, "invalid argument");
+    assert_eq!(mock.requests().len(), requests);
+}
+
+#[test]
+fn contexts_are_read_and_switched() {
+    let mock = MockWebDriver::start();
+    let session = connect(&mock);
+
+    mock.enqueue("GET", "session/*/moz/context", MockResponse::value(json!("content")));
+    assert_eq!(session.get_context().unwrap(), FirefoxContext::Content);
+    mock.enqueue("POST", "session/*/moz/context", MockResponse::value(Value::Null));
+    session.set_context(FirefoxContext::Chrome).unwrap();
+    assert_eq!(mock.last_request().unwrap().body, json!({"context": "chrome"}));
+}
+
+#[test]
+fn context_guards_restore_the_previous_context() {
+    let mock = MockWebDriver::start();
+    let session = connect(&mock);
+    mock.enqueue("GET", "session/*/moz/context", MockResponse::value(json!("content")));
+    mock.enqueue("POST", "session/*/moz/context", MockResponse::value(Value::Null));
+    mock.enqueue("POST", "session/*/moz/context", MockResponse::value(Value::Null));
+
+    {
+        let _chrome = session.scoped_context(FirefoxContext::Chrome).unwrap();
+        assert_eq!(mock.last_request().unwrap().body, json!({"context": "chrome"}));
+    }
+    let switches: Vec<Value> = mock.requests().into_iter()
+        .filter(|r| r.method == "POST" && r.path.ends_with("/moz/context"))
+        .map(|r| r.body)
+        .collect();
+    assert_eq!(switches, vec![json!({"context": "chrome"}), json!({"context": "content"})]);
+}
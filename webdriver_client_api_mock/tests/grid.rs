@@ -0,0 +1,163 @@
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_simple_impl as simple_impl;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+
+use mock::MockResponse;
+use mock::MockWebDriver;
+use serde_json::json;
+use serde_json::Value;
+use simple_impl::DefaultWebDriver;
+use simplehttp::HttpClient;
+use wda::grid::Grid;
+use wda::grid::GridStatus;
+use wda::structs::Capabilities;
+use wda::traits::WebDriver;
+
+use std::time::Duration;
+
+/// `/status` of a Selenium 4.8 hub with a busy Chrome node and a free Firefox node.
+fn selenium_status() -> Value {
+    json!({
+        "ready": true,
+        "message": "Selenium Grid ready.",
+        "nodes": [
+            {
+                "id": "7ac35b3a-1c4e-4b8a-9d0e-5e8e1a2f9c01",
+                "uri": "http://172.18.0.3:5555",
+                "maxSessions": 1,
+                "osInfo": {"arch": "amd64", "name": "Linux", "version": "5.15.0-67-generic"},
+                "heartbeatPeriod": 60000,
+                "availability": "UP",
+                "version": "4.8.0 (revision 267030adea)",
+                "slots": [{
+                    "id": {"hostId": "7ac35b3a-1c4e-4b8a-9d0e-5e8e1a2f9c01", "id": "3d5e1f4c-7d51-4a7e-8a6b-2f0c3b9e6d11"},
+                    "lastStarted": "2023-03-01T10:15:30.123Z",
+                    "session": {
+                        "capabilities": {"browserName": "chrome", "se:cdp": "ws://172.18.0.3:4444/session/6b1f/se/cdp"},
+                        "sessionId": "6b1f",
+                        "start": "2023-03-01T10:15:30.123Z",
+                        "stereotype": {"browserName": "chrome", "browserVersion": "110.0", "platformName": "LINUX"},
+                        "uri": "http://172.18.0.3:5555"
+                    },
+                    "stereotype": {"browserName": "chrome", "browserVersion": "110.0", "platformName": "LINUX", "se:noVncPort": 7900}
+                }]
+            },
+            {
+                "id": "0f9d2c71-8a43-4e55-b1d2-6c7a8e9f0a12",
+                "uri": "http://172.18.0.4:5555",
+                "maxSessions": 1,
+                "osInfo": {"arch": "amd64", "name": "Linux", "version": "5.15.0-67-generic"},
+                "heartbeatPeriod": 60000,
+                "availability": "UP",
+                "version": "4.8.0 (revision 267030adea)",
+                "slots": [{
+                    "id": {"hostId": "0f9d2c71-8a43-4e55-b1d2-6c7a8e9f0a12", "id": "9a8b7c6d-5e4f-4a3b-8c2d-1e0f9a8b7c6d"},
+                    "lastStarted": "1970-01-01T00:00:00Z",
+                    "session": null,
+                    "stereotype": {"browserName": "firefox", "browserVersion": "110.0", "platformName": "LINUX"}
+                }]
+            }
+        ]
+    })
+}
+
+fn caps(value: Value) -> Capabilities {
+    value.as_object().unwrap().clone()
+}
+
+#[test]
+fn selenium_status_payloads_are_parsed() {
+    let mock = MockWebDriver::start();
+    mock.enqueue("GET", "status", MockResponse::value(selenium_status()));
+    let driver = DefaultWebDriver::new(Box::new(HttpClient::new(mock.url().to_string())));
+
+    let status = driver.grid_status().unwrap();
+    assert!(status.ready);
+    assert_eq!(status.nodes.len(), 2);
+    let node = &status.nodes[0];
+    assert!(node.is_up());
+    assert_eq!(node.max_sessions, 1);
+    assert_eq!(node.version.as_deref(), Some("4.8.0 (revision 267030adea)"));
+    assert!(!node.slots[0].is_free());
+    assert!(status.nodes[1].slots[0].is_free());
+}
+
+#[test]
+fn free_slots_match_requested_capabilities() {
+    let status: GridStatus = serde_json::from_value(selenium_status()).unwrap();
+
+    assert!(status.free_slots(&caps(json!({"browserName": "chrome"}))).is_empty());
+    let firefox = status.free_slots(&caps(json!({"browserName": "firefox", "platformName": "linux"})));
+    assert_eq!(firefox.len(), 1);
+    assert_eq!(firefox[0].stereotype["browserName"], json!("firefox"));
+    assert_eq!(status.free_slots(&caps(json!({"platformName": "Linux"}))).len(), 1);
+    assert_eq!(status.free_slots(&caps(json!({"capabilities": {"alwaysMatch": {"browserName": "firefox"}}}))).len(), 1);
+    assert!(status.free_slots(&caps(json!({"browserName": "firefox", "platformName": "windows"}))).is_empty());
+    assert!(status.free_slots(&caps(json!({"browserName": "firefox", "browserVersion": "111.0"}))).is_empty());
+    // Extension capabilities the stereotype does not declare are left to the node
+    assert_eq!(status.free_slots(&caps(json!({"browserName": "firefox", "moz:debuggerAddress": true}))).len(), 1);
+
+    let mut unnamed = status.clone();
+    unnamed.nodes[1].slots[0].stereotype.remove("browserName");
+    assert!(unnamed.free_slots(&caps(json!({"browserName": "firefox"}))).is_empty());
+    assert_eq!(unnamed.free_slots(&caps(json!({"platformName": "LINUX"}))).len(), 1);
+
+    let mut draining = status.clone();
+    draining.nodes[1].availability = "DRAINING".to_string();
+    assert!(draining.free_slots(&caps(json!({}))).is_empty());
+}
+
+#[test]
+fn any_first_match_entry_merged_with_always_match_may_match() {
+    let status: GridStatus = serde_json::from_value(selenium_status()).unwrap();
+    let body = |always: Value, first: Value| caps(json!({"capabilities": {"alwaysMatch": always, "firstMatch": first}}));
+
+    let either = body(json!({"platformName": "linux"}), json!([{"browserName": "chrome"}, {"browserName": "firefox"}]));
+    assert_eq!(status.free_slots(&either).len(), 1);
+    let neither = body(json!({}), json!([{"browserName": "chrome"}, {"browserName": "safari"}]));
+    assert!(status.free_slots(&neither).is_empty());
+    // `alwaysMatch` applies to every entry
+    let windows = body(json!({"platformName": "windows"}), json!([{"browserName": "firefox"}]));
+    assert!(status.free_slots(&windows).is_empty());
+    assert_eq!(status.free_slots(&body(json!({"browserName": "firefox"}), json!([]))).len(), 1);
+}
+
+#[test]
+fn waiting_for_a_slot_polls_until_the_timeout() {
+    let mock = MockWebDriver::start();
+    let driver = DefaultWebDriver::new(Box::new(HttpClient::new(mock.url().to_string())));
+    let firefox = caps(json!({"browserName": "firefox"}));
+
+    let error = driver.wait_for_slot(&firefox, Duration::from_millis(100), Duration::from_millis(20)).unwrap_err();
+    assert_eq!(error.error, "timeout");
+    assert!(mock.requests().len() > 1);
+
+    let mut busy = selenium_status();
+    busy["nodes"][1]["slots"][0]["session"] = json!({"sessionId": "c3d4"});
+    mock.enqueue("GET", "status", MockResponse::value(busy));
+    mock.enqueue("GET", "status", MockResponse::value(selenium_status()));
+    mock.clear_requests();
+    driver.wait_for_slot(&firefox, Duration::from_secs(5), Duration::from_millis(10)).unwrap();
+    assert_eq!(mock.requests().len(), 2);
+}
+
+#[test]
+fn hub_base_paths_are_kept() {
+    let mock = MockWebDriver::start();
+    mock.enqueue("GET", "wd/hub/status", MockResponse::value(selenium_status()));
+    mock.enqueue("POST", "wd/hub/session", MockResponse::value(json!({
+        "sessionId": "6b1f",
+        "capabilities": {"browserName": "chrome", "se:cdp": "ws://172.18.0.3:4444/session/6b1f/se/cdp"},
+    })));
+    mock.enqueue("POST", "wd/hub/session/6b1f/url", MockResponse::value(Value::Null));
+    let driver = DefaultWebDriver::new(Box::new(HttpClient::new(format!("{}/wd/hub/", mock.url()))));
+
+    assert!(driver.grid_status().unwrap().ready);
+    let session = driver.create_session(caps(json!({"capabilities": {}}))).unwrap();
+    session.navigate_to("https://example.com/").unwrap();
+    let paths: Vec<String> = mock.requests().into_iter().map(|r| r.path).collect();
+    assert_eq!(paths, vec!["wd/hub/status", "wd/hub/session", "wd/hub/session/6b1f/url"]);
+    assert_eq!(session.se_cdp_url(), Some("ws://172.18.0.3:4444/session/6b1f/se/cdp"));
+}
@@ -0,0 +1,100 @@
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+
+use mock::MockResponse;
+use mock::MockWebDriver;
+use serde_json::json;
+use serde_json::Value;
+use simplehttp::HttpClient;
+use wda::structs::TRANSPORT_ERROR;
+use wda::traits::HttpExecutor;
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::io::Write;
+use std::net::TcpListener;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn configured_headers_are_sent() {
+    let mock = MockWebDriver::start();
+    let client = HttpClient::builder(mock.url().to_string())
+        .header("X-Team", "checkout")
+        .basic_auth("grid", "s3cret")
+        .user_agent("suite/1.0")
+        .build()
+        .unwrap();
+
+    let _: Value = client.get("status").unwrap();
+    let request = mock.last_request().unwrap();
+    assert_eq!(request.header("x-team"), Some("checkout"));
+    assert_eq!(request.header("Authorization"), Some("Basic Z3JpZDpzM2NyZXQ="));
+    assert_eq!(request.header("User-Agent"), Some("suite/1.0"));
+    assert_eq!(request.header("Accept"), Some("application/json;charset=UTF-8"));
+
+    let client = HttpClient::builder(mock.url().to_string()).bearer_auth("t0ken").build().unwrap();
+    let _: Value = client.post("session", json!({"capabilities": {}})).unwrap();
+    assert_eq!(mock.last_request().unwrap().header("Authorization"), Some("Bearer t0ken"));
+}
+
+#[test]
+fn timeouts_are_transport_errors() {
+    let mock = MockWebDriver::start();
+    mock.enqueue("GET", "status", MockResponse::value(json!({"ready": true})).delayed(Duration::from_millis(500)));
+    let client = HttpClient::builder(mock.url().to_string())
+        .timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+
+    assert_eq!(client.get::<Value>("status").unwrap_err().error, TRANSPORT_ERROR);
+}
+
+#[test]
+fn proxies_are_validated_and_unreachable_ones_fail_requests() {
+    let error = HttpClient::builder("http://localhost:4444".to_string()).proxy("ftp://proxy:3128").build().err().unwrap();
+    assert_eq!(error.error, "invalid argument");
+
+    let client = HttpClient::builder("http://localhost:4444".to_string())
+        .proxy("http://127.0.0.1:1")
+        .build()
+        .unwrap();
+    assert_eq!(client.get::<Value>("status").unwrap_err().error, TRANSPORT_ERROR);
+}
+
+#[test]
+fn undecodable_bodies_and_results_are_errors() {
+    let mock = MockWebDriver::start();
+    let client = HttpClient::new(mock.url().to_string());
+
+    let mut body = HashMap::new();
+    body.insert((1, 2), "not a JSON object key");
+    let error = client.post::<Value, _>("session", body).unwrap_err();
+    assert_eq!(error.error, "invalid argument");
+    assert!(mock.requests().is_empty());
+
+    mock.enqueue("GET", "status", MockResponse::value(json!("ready")));
+    let error = client.get::<u32>("status").unwrap_err();
+    assert_eq!(error.error, "unknown error");
+    assert!(error.message.starts_with("Cannot read response: "));
+}
+
+#[test]
+fn unexpected_responses_are_reported_without_their_body() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = [0; 1024];
+        let _ = stream.read(&mut request).unwrap();
+        let body = "<html>Authorization: Bearer t0ken</html>";
+        write!(stream, "HTTP/1.1 502 Bad Gateway\r\nContent-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+    });
+
+    let error = HttpClient::new(url).get::<Value>("status").unwrap_err();
+    server.join().unwrap();
+    assert_eq!(error.error, "unknown error");
+    assert_eq!(error.message, "Unexpected HTTP 502 response of 40 bytes");
+}
@@ -0,0 +1,135 @@
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_simple_impl as simple_impl;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+
+mod common;
+
+use mock::state::MockElement;
+use mock::MockResponse;
+use mock::MockWebDriver;
+use serde_json::json;
+use simplehttp::HttpClient;
+use wda::by::By;
+use wda::structs::Frame;
+use wda::structs::WebDriverLocator;
+use wda::traits::SessionHandle;
+use wda::WEB_ELEMENT_IDENTIFIER;
+
+fn connect(mock: &MockWebDriver) -> SessionHandle<HttpClient> {
+    let http = HttpClient::builder(mock.url().to_string()).json_wire(true).build().unwrap();
+    common::connect_with(http, json!({
+        "capabilities": {"alwaysMatch": {"browserName": "firefox"}, "firstMatch": [{"platformName": "linux"}]},
+    }))
+}
+
+#[test]
+fn new_session_sends_desired_capabilities() {
+    let mock = MockWebDriver::start();
+    mock.set_json_wire(true);
+    let session = connect(&mock);
+
+    let request = &mock.requests()[0];
+    assert_eq!(request.body["desiredCapabilities"], json!({"browserName": "firefox", "platformName": "linux"}));
+    assert_eq!(session.get_session_id(), mock.state().sessions.keys().next().unwrap());
+    assert_eq!(session.get_capabilities()["browserName"], json!("firefox"));
+}
+
+#[test]
+fn legacy_element_references_are_decoded() {
+    let mock = MockWebDriver::start();
+    mock.set_json_wire(true);
+    let first = mock.add_element("css selector", "li", MockElement::new("li").text("one"));
+    let second = mock.add_element("css selector", "li", MockElement::new("li").text("two"));
+    let session = connect(&mock);
+
+    let element = session.find_element(&WebDriverLocator::CSS, "li").unwrap();
+    assert_eq!(element.get_reference_id(), first);
+    assert_eq!(element.get_text().unwrap(), "one");
+
+    let ids: Vec<String> = session.find_elements(&WebDriverLocator::CSS, "li").unwrap()
+        .iter()
+        .map(|e| e.get_reference_id().to_string())
+        .collect();
+    assert_eq!(ids, vec![first, second]);
+}
+
+#[test]
+fn numeric_statuses_become_errors() {
+    let mock = MockWebDriver::start();
+    mock.set_json_wire(true);
+    let session = connect(&mock);
+
+    let error = session.find_element(&WebDriverLocator::CSS, "#missing").err().unwrap();
+    assert_eq!(error.error, "no such element");
+    assert!(error.message.contains("#missing"));
+    assert_eq!(session.get_alert_text().unwrap_err().error, "no such alert");
+}
+
+#[test]
+fn unexpected_alerts_keep_their_text_and_stack_trace() {
+    let mock = MockWebDriver::start();
+    mock.set_json_wire(true);
+    let session = connect(&mock);
+
+    mock.open_alert("Leave page?");
+    let error = session.get_title().unwrap_err();
+    assert_eq!(error.error, "unexpected alert open");
+    assert_eq!(error.alert_text(), Some("Leave page?"));
+
+    // Shape of a Selenium server speaking JSON Wire
+    mock.enqueue("GET", "session/*/title", MockResponse {
+        status: 500,
+        value: json!({
+            "error": "unexpected alert open",
+            "message": "unexpected alert open: {Alert text : Sure?}",
+            "alert": {"text": "Sure?"},
+            "class": "org.openqa.selenium.UnhandledAlertException",
+            "stackTrace": [{"className": "Driver", "methodName": "title", "fileName": "Driver.java", "lineNumber": 42}],
+        }),
+        ..MockResponse::value(json!(null))
+    });
+    let error = session.get_title().unwrap_err();
+    assert_eq!(error.alert_text(), Some("Sure?"));
+    assert_eq!(error.stacktrace, "at Driver.title(Driver.java:42)");
+    assert_eq!(error.data.unwrap()["class"], json!("org.openqa.selenium.UnhandledAlertException"));
+}
+
+#[test]
+fn w3c_responses_still_work_in_compatibility_mode() {
+    let mock = MockWebDriver::start();
+    mock.add_page("https://example.com/", "Example", "<html></html>");
+    let session = connect(&mock);
+
+    session.navigate_to("https://example.com/").unwrap();
+    assert_eq!(session.get_title().unwrap(), "Example");
+    assert_eq!(session.get_alert_text().unwrap_err().error, "no such alert");
+}
+
+#[test]
+fn status_codes_round_trip() {
+    for error in ["no such element", "stale element reference", "unexpected alert open", "unknown error"] {
+        assert_eq!(wda::jsonwire::error_code(wda::jsonwire::status_code(error)), error);
+    }
+    assert_eq!(wda::jsonwire::error_code(7), "no such element");
+    assert_eq!(wda::jsonwire::error_code(99), "unknown error");
+}
+
+#[test]
+fn element_arguments_carry_the_legacy_key_only_in_compatibility_mode() {
+    let mock = MockWebDriver::start();
+    mock.set_json_wire(true);
+    let frame = mock.add_element("css selector", "iframe", MockElement::new("iframe"));
+    let session = connect(&mock);
+    session.switch_to_frame(Frame::from(By::css("iframe"))).unwrap();
+    assert_eq!(mock.last_request().unwrap().body, json!({"id": {
+        WEB_ELEMENT_IDENTIFIER: frame,
+        "ELEMENT": frame,
+    }}));
+
+    mock.set_json_wire(false);
+    let session = common::connect(&mock);
+    session.switch_to_frame(Frame::from(By::css("iframe"))).unwrap();
+    assert_eq!(mock.last_request().unwrap().body, json!({"id": {WEB_ELEMENT_IDENTIFIER: frame}}));
+}
@@ -0,0 +1,30 @@
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_simple_impl as simple_impl;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+
+mod common;
+
+use common::connect;
+use mock::state::MockElement;
+use mock::MockWebDriver;
+use serde_json::json;
+use wda::by::By;
+use wda::keys::Key;
+use wda::keys::KeySequence;
+
+#[test]
+fn send_keys_transmits_validated_sequences() {
+    let mock = MockWebDriver::start();
+    mock.add_element("css selector", "input", MockElement::new("input"));
+    let session = connect(&mock);
+    let input = session.find_element_by(&By::css("input")).unwrap();
+
+    input.send_keys(KeySequence::new().chord(&[Key::Shift], "a").as_str()).unwrap();
+    assert_eq!(mock.last_request().unwrap().body, json!({"text": "\u{E008}a\u{E000}"}));
+
+    let requests = mock.requests().len();
+    assert_eq!(input.send_keys("a\u{E030}").unwrap_err().error, "invalid argument");
+    assert_eq!(mock.requests().len(), requests);
+}
@@ -0,0 +1,74 @@
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_simple_impl as simple_impl;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+
+mod common;
+
+use common::connect;
+use mock::MockResponse;
+use mock::MockWebDriver;
+use serde_json::json;
+use wda::logs::LogEntry;
+use wda::logs::LogLevel;
+
+#[test]
+fn log_endpoints_are_preferred() {
+    let mock = MockWebDriver::start();
+    let session = connect(&mock);
+    mock.log_to_console("INFO", "before capture");
+    let capture = session.capture_logs().unwrap();
+    assert!(!capture.is_hooked());
+
+    mock.log_to_console("WARNING", "deprecated API");
+    assert_eq!(capture.entries().unwrap(), vec![LogEntry {
+        level: LogLevel::Warning,
+        message: "deprecated API".to_string(),
+        source: Some("console-api".to_string()),
+        timestamp: 1_700_000_000_000,
+    }]);
+    assert_eq!(mock.last_request().unwrap().body, json!({"type": "browser"}));
+    assert!(mock.last_request().unwrap().path.ends_with("/se/log"));
+    assert!(capture.entries().unwrap().is_empty());
+}
+
+#[test]
+fn severe_entries_fail_the_assertion() {
+    let mock = MockWebDriver::start();
+    let session = connect(&mock);
+    let capture = session.capture_logs().unwrap();
+
+    mock.log_to_console("FINE", "rendered");
+    capture.assert_no_severe_errors().unwrap();
+
+    mock.log_to_console("SEVERE", "Uncaught TypeError: x is undefined");
+    mock.log_to_console("SEVERE", "Failed to load resource");
+    let error = capture.assert_no_severe_errors().unwrap_err();
+    assert_eq!(error.error, "javascript error");
+    assert_eq!(error.message, "2 severe browser log entries: Uncaught TypeError: x is undefined; Failed to load resource");
+}
+
+#[test]
+fn hook_is_used_without_log_endpoints() {
+    let mock = MockWebDriver::start();
+    let session = connect(&mock);
+    mock.enqueue_error("POST", "session/*/se/log", "unknown command", "se/log");
+    mock.enqueue_error("POST", "session/*/log", "unknown command", "log");
+    mock.enqueue("POST", "session/*/execute/sync", MockResponse::value(json!([])));
+    mock.enqueue("POST", "session/*/execute/sync", MockResponse::value(json!([
+        {"level": "SEVERE", "message": "boom (app.js:3)", "source": "javascript", "timestamp": 1},
+        {"level": "DEBUG", "message": "{\"a\":1}", "source": "console-api", "timestamp": 2},
+    ])));
+
+    let capture = session.capture_logs().unwrap();
+    assert!(capture.is_hooked());
+    let entries = capture.entries().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].level, LogLevel::Severe);
+    assert_eq!(entries[1].message, "{\"a\":1}");
+    assert!(mock.last_request().unwrap().body["script"].as_str().unwrap().contains("window.__webdriverLogs"));
+
+    mock.enqueue("POST", "session/*/execute/sync", MockResponse::value(json!(null)));
+    assert_eq!(capture.entries().unwrap_err().error, "unknown error");
+}
@@ -0,0 +1,113 @@
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_simple_impl as simple_impl;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+
+use mock::MockWebDriver;
+use serde_json::json;
+use simple_impl::DefaultWebDriver;
+use simplehttp::HttpClient;
+use wda::pool::SessionPool;
+
+use std::thread;
+use std::time::Duration;
+
+fn pool(mock: &MockWebDriver, max_size: usize) -> SessionPool<HttpClient, DefaultWebDriver<HttpClient>> {
+    let driver = DefaultWebDriver::new(Box::new(HttpClient::new(mock.url().to_string())));
+    let caps = json!({"capabilities": {"alwaysMatch": {"browserName": "firefox"}}});
+    SessionPool::new(driver, caps.as_object().unwrap().clone(), max_size)
+}
+
+#[test]
+fn sessions_are_created_lazily_and_reused() {
+    let mock = MockWebDriver::start();
+    let pool = pool(&mock, 2);
+    assert_eq!(pool.size(), 0);
+
+    let first_id = pool.lease().unwrap().get_session_id().to_string();
+    assert_eq!((pool.size(), pool.idle()), (1, 1));
+
+    let lease = pool.lease().unwrap();
+    assert_eq!(lease.get_session_id(), first_id);
+    assert_eq!(mock.state().sessions.len(), 1);
+}
+
+#[test]
+fn returned_sessions_are_reset() {
+    let mock = MockWebDriver::start();
+    mock.add_page("https://example.com/", "Example", "<html></html>");
+    let pool = pool(&mock, 1);
+
+    let session_id = {
+        let lease = pool.lease().unwrap();
+        lease.navigate_to("https://example.com/").unwrap();
+        let session_id = lease.get_session_id().to_string();
+        {
+            let mut state = mock.state();
+            let session = state.sessions.get_mut(&session_id).unwrap();
+            session.windows.push("popup".to_string());
+            session.cookies.push(json!({"name": "token", "value": "secret"}));
+        }
+        mock.open_alert("Leave page?");
+        session_id
+    };
+
+    let state = mock.state();
+    let session = &state.sessions[&session_id];
+    assert!(session.alert.is_none());
+    assert_eq!(session.windows.len(), 1);
+    assert!(session.cookies.is_empty());
+    assert_eq!(session.current_url(), "about:blank");
+}
+
+#[test]
+fn unhealthy_sessions_are_replaced() {
+    let mock = MockWebDriver::start();
+    let pool = pool(&mock, 1);
+
+    let stale_id = pool.lease().unwrap().get_session_id().to_string();
+    mock.state().sessions.remove(&stale_id);
+
+    let lease = pool.lease().unwrap();
+    assert_ne!(lease.get_session_id(), stale_id);
+    assert_eq!(pool.size(), 1);
+}
+
+#[test]
+fn discarded_leases_delete_the_session() {
+    let mock = MockWebDriver::start();
+    let pool = pool(&mock, 1);
+
+    pool.lease().unwrap().discard();
+    assert_eq!(pool.size(), 0);
+    assert!(mock.state().sessions.is_empty());
+}
+
+#[test]
+fn lease_waits_for_a_returned_session() {
+    let mock = MockWebDriver::start();
+    let pool = pool(&mock, 1).with_acquire_timeout(Duration::from_millis(100));
+
+    let lease = pool.lease().unwrap();
+    assert_eq!(pool.lease().err().unwrap().error, "timeout");
+
+    thread::scope(|scope| {
+        let waiting = scope.spawn(|| pool.lease().map(|l| l.get_session_id().to_string()));
+        thread::sleep(Duration::from_millis(20));
+        let session_id = lease.get_session_id().to_string();
+        drop(lease);
+        assert_eq!(waiting.join().unwrap().unwrap(), session_id);
+    });
+}
+
+#[test]
+fn dropping_the_pool_deletes_idle_sessions() {
+    let mock = MockWebDriver::start();
+    {
+        let pool = pool(&mock, 2);
+        let _a = pool.lease().unwrap();
+        let _b = pool.lease().unwrap();
+    }
+    assert!(mock.state().sessions.is_empty());
+}
@@ -0,0 +1,64 @@
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_simple_impl as simple_impl;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+
+mod common;
+
+use common::connect;
+use mock::state::MockElement;
+use mock::MockWebDriver;
+use serde_json::json;
+use wda::by::By;
+use wda::structs::ElementRect;
+use wda::structs::SetWindowRect;
+use wda::structs::WindowRect;
+
+fn rect(x: f64, y: f64, width: f64, height: f64) -> ElementRect {
+    ElementRect { x, y, width, height }
+}
+
+#[test]
+fn element_rects_keep_subpixels() {
+    let mock = MockWebDriver::start();
+    let mut element = MockElement::new("span");
+    element.rect = json!({"x": 8.5, "y": 10.25, "width": 100.75, "height": 18});
+    mock.add_element("css selector", "span", element);
+    let session = connect(&mock);
+
+    let bounds = session.find_element_by(&By::css("span")).unwrap().get_rect().unwrap();
+    assert_eq!(bounds, rect(8.5, 10.25, 100.75, 18.0));
+    assert_eq!(bounds.center(), (58.875, 19.25));
+}
+
+#[test]
+fn window_rects_update_only_given_fields() {
+    let mock = MockWebDriver::start();
+    let session = connect(&mock);
+
+    let resized = session.set_window_rect(SetWindowRect::resize(800, 600)).unwrap();
+    assert_eq!(mock.last_request().unwrap().body, json!({"width": 800, "height": 600}));
+    assert_eq!(resized, WindowRect { x: 0, y: 0, width: 800, height: 600 });
+
+    let moved = session.set_window_rect(SetWindowRect::move_to(-20, 40)).unwrap();
+    assert_eq!(mock.last_request().unwrap().body, json!({"x": -20, "y": 40}));
+    assert_eq!(moved, WindowRect { x: -20, y: 40, width: 800, height: 600 });
+
+    let full = WindowRect { x: 1, y: 2, width: 3, height: 4 };
+    assert_eq!(session.set_window_rect(full.into()).unwrap(), full);
+    assert_eq!(session.get_window_rect().unwrap(), full);
+}
+
+#[test]
+fn geometry_helpers() {
+    let outer = rect(0.0, 0.0, 100.0, 50.0);
+    assert!(outer.contains(&rect(10.0, 10.0, 90.0, 40.0)));
+    assert!(!outer.contains(&rect(10.0, 10.0, 90.5, 40.0)));
+    assert!(outer.contains_point(100.0, 50.0));
+    assert!(!outer.contains_point(-0.5, 10.0));
+
+    assert!(outer.intersects(&rect(99.5, 49.5, 10.0, 10.0)));
+    assert!(!outer.intersects(&rect(100.0, 0.0, 10.0, 10.0)));
+    assert_eq!(ElementRect::from(WindowRect { x: 1, y: 2, width: 3, height: 4 }), rect(1.0, 2.0, 3.0, 4.0));
+}
@@ -0,0 +1,79 @@
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_simple_impl as simple_impl;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+
+mod common;
+
+use mock::state::MockElement;
+use mock::MockWebDriver;
+use simplehttp::HttpClient;
+use wda::by::By;
+use wda::relative::with;
+use wda::traits::SessionHandle;
+
+struct Form {
+    mock: MockWebDriver,
+    session: SessionHandle<HttpClient>,
+    label: String,
+    email: String,
+    far: String,
+    below: String,
+}
+
+//  [Email] [email.......]            [far....]
+//  [below.......]
+fn form() -> Form {
+    let mock = MockWebDriver::start();
+    let label = mock.add_element("css selector", "#email-label", MockElement::new("label").rect(10, 10, 80, 20));
+    let far = mock.add_element("tag name", "input", MockElement::new("input").rect(400, 10, 100, 20));
+    let email = mock.add_element("tag name", "input", MockElement::new("input").rect(100, 10, 200, 20));
+    let below = mock.add_element("tag name", "input", MockElement::new("input").rect(10, 40, 200, 20));
+    let session = common::connect(&mock);
+    Form { mock, session, label, email, far, below }
+}
+
+fn ids(elements: Vec<wda::traits::ElementHandle<HttpClient>>) -> Vec<String> {
+    elements.iter().map(|e| e.get_reference_id().to_string()).collect()
+}
+
+#[test]
+fn right_of_is_sorted_by_proximity() {
+    let form = form();
+    let found = with(By::tag_name("input"))
+        .right_of(By::id("email-label"))
+        .find_all(form.session.as_ref())
+        .unwrap();
+    assert_eq!(ids(found), vec![form.email.clone(), form.far.clone()]);
+
+    let closest = with(By::tag_name("input")).right_of(By::id("email-label")).find(form.session.as_ref()).unwrap();
+    assert_eq!(closest.get_reference_id(), form.email);
+}
+
+#[test]
+fn filters_combine() {
+    let form = form();
+    let label = form.session.find_element_by(&By::id("email-label")).unwrap();
+    assert_eq!(label.get_reference_id(), form.label);
+
+    let below = with(By::tag_name("input")).below(&label).left_of(By::css("#email-label"));
+    assert!(below.find_all(form.session.as_ref()).unwrap().is_empty());
+
+    let below = with(By::tag_name("input")).below(&label).near(&label);
+    assert_eq!(ids(below.find_all(form.session.as_ref()).unwrap()), vec![form.below.clone()]);
+
+    let above = with::<HttpClient>(By::tag_name("input")).above(By::id("email-label"));
+    assert_eq!(above.find(form.session.as_ref()).err().unwrap().error, "no such element");
+}
+
+#[test]
+fn near_uses_edge_distance() {
+    let form = form();
+    let near = with(By::tag_name("input")).near(By::id("email-label"));
+    assert_eq!(ids(near.find_all(form.session.as_ref()).unwrap()), vec![form.below.clone(), form.email.clone()]);
+
+    let within = with(By::tag_name("input")).near_within(By::id("email-label"), 5.0);
+    assert!(within.find_all(form.session.as_ref()).unwrap().is_empty());
+    assert!(form.mock.requests().iter().any(|r| r.path.ends_with("/rect")));
+}
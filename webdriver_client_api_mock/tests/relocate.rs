@@ -0,0 +1,99 @@
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_simple_impl as simple_impl;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+
+mod common;
+
+use common::connect;
+use mock::state::MockElement;
+use mock::MockWebDriver;
+use wda::by::By;
+
+#[test]
+fn stale_elements_are_found_again() {
+    let mock = MockWebDriver::start();
+    let old = mock.add_element("css selector", "#total", MockElement::new("span").text("0"));
+    let session = connect(&mock);
+    let total = session.find_element_by(&By::css("#total")).unwrap();
+
+    mock.remove_element(&old);
+    let new = mock.add_element("css selector", "#total", MockElement::new("span").text("42"));
+
+    assert_eq!(total.get_text().unwrap(), "42");
+    assert_eq!(total.get_reference_id(), new);
+    let paths: Vec<String> = mock.requests().iter().map(|r| r.path.clone()).collect();
+    assert!(paths.ends_with(&[
+        format!("session/{}/element/{}/text", session.get_session_id(), old),
+        format!("session/{}/element", session.get_session_id()),
+        format!("session/{}/element/{}/text", session.get_session_id(), new),
+    ]));
+}
+
+#[test]
+fn relocation_can_be_disabled() {
+    let mock = MockWebDriver::start();
+    let old = mock.add_element("css selector", "button", MockElement::new("button"));
+    let session = connect(&mock);
+    let button = session.find_element_by(&By::css("button")).unwrap().relocating(false);
+
+    mock.remove_element(&old);
+    mock.add_element("css selector", "button", MockElement::new("button"));
+
+    assert_eq!(button.click().unwrap_err().error, "stale element reference");
+    assert_eq!(button.get_reference_id(), old);
+    button.relocating(true).get_text().unwrap();
+    assert_ne!(button.get_reference_id(), old);
+}
+
+#[test]
+fn stale_actions_are_only_replayed_if_opted_in() {
+    let mock = MockWebDriver::start();
+    let old = mock.add_element("css selector", "button", MockElement::new("button"));
+    let session = connect(&mock);
+    let button = session.find_element_by(&By::css("button")).unwrap();
+
+    mock.remove_element(&old);
+    let new = mock.add_element("css selector", "button", MockElement::new("button"));
+    mock.clear_requests();
+
+    assert_eq!(button.click().unwrap_err().error, "stale element reference");
+    assert_eq!(mock.requests().len(), 1);
+    assert_eq!(button.get_reference_id(), old);
+
+    button.replaying(true).click().unwrap();
+    let clicked = mock.last_request().unwrap();
+    assert_eq!(clicked.path, format!("session/{}/element/{}/click", session.get_session_id(), new));
+}
+
+#[test]
+fn chains_and_positions_are_replayed() {
+    let mock = MockWebDriver::start();
+    let list = mock.add_element("css selector", "ul", MockElement::new("ul"));
+    mock.add_child_element(&list, "tag name", "li", MockElement::new("li").text("a"));
+    mock.add_child_element(&list, "tag name", "li", MockElement::new("li").text("b"));
+    let session = connect(&mock);
+    let second = session.find_element_by(&By::css("ul")).unwrap()
+        .find_elements_by(&By::tag_name("li")).unwrap()
+        .remove(1);
+
+    mock.remove_element(&list);
+    let list = mock.add_element("css selector", "ul", MockElement::new("ul"));
+    mock.add_child_element(&list, "tag name", "li", MockElement::new("li").text("c"));
+    mock.add_child_element(&list, "tag name", "li", MockElement::new("li").text("d"));
+
+    assert_eq!(second.get_text().unwrap(), "d");
+}
+
+#[test]
+fn elements_without_locator_stay_stale() {
+    let mock = MockWebDriver::start();
+    let input = mock.add_element("css selector", "input", MockElement::new("input"));
+    mock.state().active_element = Some(input.clone());
+    let session = connect(&mock);
+    let active = session.get_active_element().unwrap();
+
+    mock.remove_element(&input);
+    assert_eq!(active.get_text().unwrap_err().error, "stale element reference");
+}
@@ -0,0 +1,28 @@
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_simple_impl as simple_impl;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+
+mod common;
+
+use mock::MockResponse;
+use mock::MockWebDriver;
+use serde_json::json;
+use wda::structs::ExecuteScript;
+
+#[test]
+fn error_bodies_are_not_decoded_as_results() {
+    let mock = MockWebDriver::start();
+    let session = common::connect(&mock);
+    let script = || ExecuteScript { script: "return 1".to_string(), args: Vec::new() };
+
+    mock.enqueue_error("POST", "session/*/execute/sync", "javascript error", "x is not defined");
+    let error = session.execute_sync(script()).unwrap_err();
+    assert_eq!((error.error.as_str(), error.message.as_str()), ("javascript error", "x is not defined"));
+
+    // Successful results shaped like errors are still results
+    let lookalike = json!({"error": "none", "message": "", "stacktrace": ""});
+    mock.enqueue("POST", "session/*/execute/sync", MockResponse::value(lookalike.clone()));
+    assert_eq!(session.execute_sync(script()).unwrap(), lookalike);
+}
@@ -0,0 +1,123 @@
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_simple_impl as simple_impl;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+
+mod common;
+
+use mock::state::MockElement;
+use mock::MockResponse;
+use mock::MockWebDriver;
+use serde_json::json;
+use serde_json::Value;
+use simplehttp::HttpClient;
+use wda::by::By;
+use wda::retry::RetryExecutor;
+use wda::retry::RetryPolicy;
+use wda::structs::TRANSPORT_ERROR;
+use wda::traits::HttpExecutor;
+
+use std::cell::RefCell;
+use std::time::Duration;
+
+thread_local! {
+    static SLEPT: RefCell<Vec<Duration>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Records the delays instead of waiting, each test runs on its own thread.
+fn record(delay: Duration) {
+    SLEPT.with(|slept| slept.borrow_mut().push(delay));
+}
+
+fn slept() -> Vec<Duration> {
+    SLEPT.with(|slept| slept.take())
+}
+
+fn quick() -> RetryPolicy {
+    RetryPolicy::new().jitter(0.0).sleeper(record)
+}
+
+fn unavailable() -> MockResponse {
+    MockResponse { status: 503, ..MockResponse::error("unknown error", "No node available") }
+}
+
+fn count(mock: &MockWebDriver, method: &str, suffix: &str) -> usize {
+    mock.requests().iter().filter(|r| r.method == method && r.path.ends_with(suffix)).count()
+}
+
+#[test]
+fn gets_are_retried_on_server_errors() {
+    let mock = MockWebDriver::start();
+    let retry = RetryExecutor::new(Box::new(HttpClient::new(mock.url().to_string())), quick().max_retries(2));
+    mock.enqueue("GET", "status", unavailable());
+    mock.enqueue("GET", "status", unavailable());
+    let status: Value = retry.get("status").unwrap();
+    assert_eq!(status["ready"], json!(true));
+    assert_eq!(count(&mock, "GET", "status"), 3);
+
+    mock.clear_requests();
+    for _ in 0..3 {
+        mock.enqueue("GET", "status", unavailable());
+    }
+    let error = retry.get::<Value>("status").unwrap_err();
+    assert_eq!(error.data.unwrap()["status"], json!(503));
+    assert_eq!(count(&mock, "GET", "status"), 3);
+}
+
+#[test]
+fn gets_are_retried_on_transport_errors() {
+    let mock = MockWebDriver::start();
+    let client = HttpClient::builder(mock.url().to_string()).timeout(Duration::from_millis(100)).build().unwrap();
+    let retry = RetryExecutor::new(Box::new(client), quick().max_retries(1));
+    let slow = || MockResponse::value(json!({})).delayed(Duration::from_millis(300));
+    mock.enqueue("GET", "status", slow());
+    mock.enqueue("GET", "status", slow());
+
+    assert_eq!(retry.get::<Value>("status").unwrap_err().error, TRANSPORT_ERROR);
+    // The mock serves one request at a time, so the retry shows as a wait rather than a request
+    assert_eq!(slept(), vec![Duration::from_millis(200)]);
+}
+
+#[test]
+fn clicks_are_not_retried_by_default() {
+    let mock = MockWebDriver::start();
+    mock.add_element("css selector", "button", MockElement::new("button"));
+    let retry = RetryExecutor::new(Box::new(HttpClient::new(mock.url().to_string())), quick());
+    let session = common::connect_with(retry, json!({"capabilities": {}}));
+    let button = session.find_element_by(&By::css("button")).unwrap();
+
+    mock.enqueue("POST", "session/*/element/*/click", unavailable());
+    assert!(button.click().is_err());
+    assert_eq!(count(&mock, "POST", "/click"), 1);
+}
+
+#[test]
+fn delays_are_capped() {
+    let mock = MockWebDriver::start();
+    let policy = quick().max_retries(3).delays(Duration::from_millis(50), Duration::from_millis(60));
+    let retry = RetryExecutor::new(Box::new(HttpClient::new(mock.url().to_string())), policy);
+    for _ in 0..4 {
+        mock.enqueue("GET", "status", unavailable());
+    }
+
+    assert!(retry.get::<Value>("status").is_err());
+    // Uncapped doubling would wait 50, 100 and 200 ms
+    let expected = [50, 60, 60].iter().map(|ms| Duration::from_millis(*ms)).collect::<Vec<_>>();
+    assert_eq!(slept(), expected);
+    assert_eq!(count(&mock, "GET", "status"), 4);
+}
+
+#[test]
+fn seeded_jitter_is_reproducible() {
+    let policy = RetryPolicy::new().delays(Duration::from_millis(100), Duration::from_secs(1)).jitter(0.5);
+    let delays = |policy: &RetryPolicy| (0..4).map(|attempt| policy.delay(attempt)).collect::<Vec<_>>();
+
+    let seeded = delays(&policy.clone().jitter_seed(7));
+    assert_eq!(seeded, delays(&policy.clone().jitter_seed(7)));
+    assert_ne!(seeded, delays(&policy.clone().jitter_seed(8)));
+    for (attempt, delay) in seeded.iter().enumerate() {
+        let exponential = Duration::from_millis(100 << attempt);
+        assert!(*delay <= exponential && *delay >= exponential / 2, "{:?}", delay);
+    }
+}
@@ -0,0 +1,130 @@
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_simple_impl as simple_impl;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+
+mod common;
+
+use common::connect;
+use mock::state::MockElement;
+use mock::MockWebDriver;
+use serde_json::json;
+use serde_json::Value;
+use simplehttp::HttpClient;
+use wda::by::By;
+use wda::scope::ScopedSession;
+use wda::structs::Frame;
+use wda::structs::WebDriverError;
+use wda::traits::SessionHandle;
+
+use std::panic;
+
+fn frame_path(mock: &MockWebDriver, session: &SessionHandle<HttpClient>) -> Vec<Value> {
+    mock.state().sessions[session.get_session_id()].frame_path.clone()
+}
+
+fn current_window(mock: &MockWebDriver, session: &SessionHandle<HttpClient>) -> Option<String> {
+    mock.state().sessions[session.get_session_id()].current_window.clone()
+}
+
+#[test]
+fn in_frame_switches_back_to_the_parent() {
+    let mock = MockWebDriver::start();
+    let editor = mock.add_element("css selector", "#editor", MockElement::new("iframe"));
+    let session = connect(&mock);
+
+    let depth = session.in_frame(By::id("editor"), |s| {
+        assert_eq!(s.get_frame_path().len(), 1);
+        Ok(frame_path(&mock, &session).len())
+    }).unwrap();
+
+    assert_eq!(depth, 1);
+    assert!(frame_path(&mock, &session).is_empty());
+    assert!(session.get_frame_path().is_empty());
+    assert!(mock.last_request().unwrap().path.ends_with("/frame/parent"));
+
+    let frame = session.find_element_by(&By::id("editor")).unwrap();
+    session.in_frame(&frame, |s| {
+        assert!(s.get_frame_path() == vec![Frame::Elem(editor.clone())]);
+        Ok(())
+    }).unwrap();
+}
+
+#[test]
+fn nested_frames_are_replayed_after_errors() {
+    let mock = MockWebDriver::start();
+    let outer = mock.add_element("css selector", "#outer", MockElement::new("iframe"));
+    mock.add_element("css selector", "#inner", MockElement::new("iframe"));
+    let session = connect(&mock);
+    session.switch_to_frame(Frame::from(By::id("outer"))).unwrap();
+
+    let err = session.in_frame(By::id("inner"), |s| {
+        s.switch_to_frame(Frame::None)?;
+        s.switch_to_frame(Frame::Id(2))?;
+        Err::<(), _>(WebDriverError::new("no such element", "missing"))
+    }).unwrap_err();
+
+    assert_eq!(err.error, "no such element");
+    let path = frame_path(&mock, &session);
+    assert_eq!(path.len(), 1);
+    assert_eq!(wda::element_reference(&path[0]), Some(outer.as_str()));
+}
+
+#[test]
+fn panics_restore_the_frame_path() {
+    let mock = MockWebDriver::start();
+    mock.add_element("css selector", "#editor", MockElement::new("iframe"));
+    let session = connect(&mock);
+    session.switch_to_frame(Frame::Id(0)).unwrap();
+
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        session.in_frame(By::id("editor"), |_| -> Result<(), WebDriverError> { panic!("boom") })
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(frame_path(&mock, &session), vec![json!(0)]);
+}
+
+#[test]
+fn in_window_restores_window_and_frames() {
+    let mock = MockWebDriver::start();
+    let session = connect(&mock);
+    let original = session.get_window_handle().unwrap();
+    let popup = mock.state().next_id("window");
+    mock.state().sessions.get_mut(session.get_session_id()).unwrap().windows.push(popup.clone());
+    session.switch_to_frame(Frame::Id(1)).unwrap();
+
+    let title = session.in_window(&popup, |s| {
+        assert!(s.get_frame_path().is_empty());
+        s.get_window_handle()
+    }).unwrap();
+
+    assert_eq!(title, popup);
+    assert_eq!(current_window(&mock, &session), Some(original.clone()));
+    assert_eq!(frame_path(&mock, &session), vec![json!(1)]);
+
+    assert_eq!(session.in_window("missing", |_| Ok(())).unwrap_err().error, "no such window");
+    assert_eq!(current_window(&mock, &session), Some(original));
+}
+
+#[test]
+fn history_navigation_leaves_frames() {
+    let mock = MockWebDriver::start();
+    mock.add_page("https://example.com/a", "A", "");
+    mock.add_page("https://example.com/b", "B", "");
+    let session = connect(&mock);
+    session.navigate_to("https://example.com/a").unwrap();
+    session.navigate_to("https://example.com/b").unwrap();
+
+    session.switch_to_frame(Frame::Id(0)).unwrap();
+    session.back().unwrap();
+    assert!(session.get_frame_path().is_empty());
+    assert!(frame_path(&mock, &session).is_empty());
+
+    session.switch_to_frame(Frame::Id(0)).unwrap();
+    session.forward().unwrap();
+    assert!(session.get_frame_path().is_empty());
+    assert!(frame_path(&mock, &session).is_empty());
+    assert_eq!(session.get_title().unwrap(), "B");
+}
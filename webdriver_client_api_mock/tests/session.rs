@@ -0,0 +1,182 @@
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_simple_impl as simple_impl;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+
+mod common;
+
+use mock::state::MockElement;
+use mock::MockWebDriver;
+use serde_json::json;
+use simple_impl::DefaultSession;
+use simple_impl::DefaultWebDriver;
+use simplehttp::HttpClient;
+use std::thread;
+use wda::structs::PermissionDescriptor;
+use wda::structs::PermissionState;
+use wda::structs::WebDriverLocator;
+use wda::traits::ElementHandle;
+use wda::traits::SessionHandle;
+
+fn connect(mock: &MockWebDriver) -> (SessionHandle<HttpClient>, String) {
+    let session = common::connect_with(
+        HttpClient::new(mock.url().to_string()),
+        json!({"capabilities": {"alwaysMatch": {"browserName": "firefox"}}}),
+    );
+    let session_id = mock.state().sessions.keys().next().unwrap().clone();
+    (session, session_id)
+}
+
+#[test]
+fn create_session_sends_capabilities() {
+    let mock = MockWebDriver::start();
+    let (_session, session_id) = connect(&mock);
+
+    let request = mock.last_request().unwrap();
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.path, "session");
+    assert_eq!(request.body, json!({"capabilities": {"alwaysMatch": {"browserName": "firefox"}}}));
+    assert_eq!(mock.state().sessions[&session_id].capabilities["browserName"], json!("firefox"));
+}
+
+#[test]
+fn navigation_uses_session_paths() {
+    let mock = MockWebDriver::start();
+    mock.add_page("https://example.com/", "Example", "<html></html>");
+    let (session, session_id) = connect(&mock);
+
+    session.navigate_to("https://example.com/").unwrap();
+    let request = mock.last_request().unwrap();
+    assert_eq!(request.path, format!("session/{}/url", session_id));
+    assert_eq!(request.body, json!({"url": "https://example.com/"}));
+
+    assert_eq!(session.get_title().unwrap(), "Example");
+    session.back().unwrap();
+    assert_eq!(session.get_current_url().unwrap(), "about:blank");
+}
+
+#[test]
+fn element_commands_reach_element_endpoints() {
+    let mock = MockWebDriver::start();
+    let element_id = mock.add_element("css selector", "#email", MockElement::new("input"));
+    let (session, session_id) = connect(&mock);
+
+    let element = session.find_element(&WebDriverLocator::CSS, "#email").unwrap();
+    assert_eq!(element.get_reference_id(), element_id);
+    element.send_keys("secret").unwrap();
+
+    let request = mock.last_request().unwrap();
+    assert_eq!(request.path, format!("session/{}/element/{}/value", session_id, element_id));
+    assert_eq!(request.body, json!({"text": "secret"}));
+    assert_eq!(element.get_property("value").unwrap(), "secret");
+}
+
+#[test]
+fn invalid_key_is_rejected_before_sending() {
+    let mock = MockWebDriver::start();
+    mock.add_element("css selector", "#email", MockElement::new("input"));
+    let (session, _) = connect(&mock);
+    let element = session.find_element(&WebDriverLocator::CSS, "#email").unwrap();
+    mock.clear_requests();
+
+    let error = element.send_keys("\u{E0FF}").unwrap_err();
+    assert_eq!(error.error, "invalid argument");
+    assert!(mock.requests().is_empty());
+}
+
+#[test]
+fn enqueued_errors_take_precedence() {
+    let mock = MockWebDriver::start();
+    let (session, _) = connect(&mock);
+    mock.enqueue_error("GET", "session/*/title", "unknown error", "boom");
+
+    let error = session.get_title().unwrap_err();
+    assert_eq!(error.error, "unknown error");
+    assert_eq!(error.message, "boom");
+    assert_eq!(session.get_title().unwrap(), "");
+}
+
+#[test]
+fn alerts_are_scriptable() {
+    let mock = MockWebDriver::start();
+    let (session, _) = connect(&mock);
+    mock.open_alert("Are you sure?");
+
+    assert_eq!(session.get_alert_text().unwrap(), "Are you sure?");
+    session.accept_alert().unwrap();
+    assert_eq!(session.get_alert_text().unwrap_err().error, "no such alert");
+}
+
+#[test]
+fn permissions_are_set_per_descriptor() {
+    let mock = MockWebDriver::start();
+    let (session, session_id) = connect(&mock);
+
+    session.set_permission(&PermissionDescriptor::midi(true), PermissionState::Granted).unwrap();
+    assert_eq!(mock.last_request().unwrap().path, format!("session/{}/permissions", session_id));
+    assert_eq!(mock.last_request().unwrap().body, json!({
+        "descriptor": {"name": "midi", "sysex": true},
+        "state": "granted",
+    }));
+
+    session.set_permission(&PermissionDescriptor::clipboard_read(), PermissionState::Denied).unwrap();
+    let permissions = mock.state().sessions[&session_id].permissions.clone();
+    assert_eq!(permissions["clipboard-read"], json!("denied"));
+    assert_eq!(permissions["midi"], json!("granted"));
+}
+
+#[test]
+fn delete_session_ends_it() {
+    let mock = MockWebDriver::start();
+    let (session, session_id) = connect(&mock);
+
+    session.delete_session().unwrap();
+    assert_eq!(mock.last_request().unwrap().path, format!("session/{}", session_id));
+    assert!(mock.state().sessions.is_empty());
+    assert_eq!(session.get_title().unwrap_err().error, "invalid session id");
+}
+
+#[test]
+fn session_is_shared_across_threads() {
+    let mock = MockWebDriver::start();
+    mock.add_page("https://example.com/", "Example", "<html></html>");
+    let (session, _) = connect(&mock);
+    session.navigate_to("https://example.com/").unwrap();
+
+    let workers: Vec<_> = (0..4)
+        .map(|_| {
+            let session = session.clone();
+            thread::spawn(move || session.get_title().unwrap())
+        })
+        .collect();
+
+    for worker in workers {
+        assert_eq!(worker.join().unwrap(), "Example");
+    }
+}
+
+#[test]
+fn handles_are_send_sync_and_clone() {
+    fn assert_handle<T: Send + Sync + Clone>() {}
+
+    assert_handle::<SessionHandle<HttpClient>>();
+    assert_handle::<ElementHandle<HttpClient>>();
+    assert_handle::<DefaultSession<HttpClient>>();
+    assert_handle::<DefaultWebDriver<HttpClient>>();
+}
+
+#[test]
+fn find_elements_decodes_every_reference() {
+    let mock = MockWebDriver::start();
+    let first = mock.add_element("css selector", "li", MockElement::new("li"));
+    let second = mock.add_element("css selector", "li", MockElement::new("li"));
+    let (session, _) = connect(&mock);
+
+    let ids: Vec<String> = session.find_elements(&WebDriverLocator::CSS, "li").unwrap()
+        .iter()
+        .map(|e| e.get_reference_id().to_string())
+        .collect();
+    assert_eq!(ids, vec![first, second]);
+    assert!(session.find_elements(&WebDriverLocator::CSS, "p").unwrap().is_empty());
+}
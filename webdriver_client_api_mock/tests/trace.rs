@@ -0,0 +1,97 @@
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_simple_impl as simple_impl;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+
+use mock::state::MockElement;
+use mock::MockResponse;
+use mock::MockWebDriver;
+use serde_json::json;
+use serde_json::Value;
+use simple_impl::DefaultWebDriver;
+use simplehttp::HttpClient;
+use wda::by::By;
+use wda::trace::CommandEvent;
+use wda::trace::TracingExecutor;
+use wda::traits::HttpExecutor;
+use wda::traits::WebDriver;
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+fn traced(mock: &MockWebDriver) -> (DefaultWebDriver<TracingExecutor<HttpClient>>, Arc<Mutex<Vec<CommandEvent>>>) {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+    let tracing = TracingExecutor::new(Box::new(HttpClient::new(mock.url().to_string())))
+        .with_sink(Arc::new(move |event: &CommandEvent| sink.lock().unwrap().push(event.clone())));
+    (DefaultWebDriver::new(Box::new(tracing)), events)
+}
+
+#[test]
+fn typed_text_is_redacted() {
+    let mock = MockWebDriver::start();
+    let id = mock.add_element("css selector", "input", MockElement::new("input"));
+    let (driver, events) = traced(&mock);
+    let session = driver.create_session(json!({"capabilities": {}}).as_object().unwrap().clone()).unwrap();
+
+    session.find_element_by(&By::css("input")).unwrap().send_keys("hunter2").unwrap();
+    mock.open_alert("Password?");
+    session.alert().unwrap().send_keys("hunter2").unwrap();
+
+    let events = events.lock().unwrap();
+    let typed = &events[events.len() - 3];
+    assert_eq!(typed.method, "POST");
+    assert_eq!(typed.session_id.as_deref(), Some(session.get_session_id()));
+    assert_eq!(typed.element_id.as_deref(), Some(id.as_str()));
+    assert_eq!(typed.body, Some(json!({"text": "***"})));
+    assert_eq!(typed.outcome(), "ok");
+    let prompt = events.last().unwrap();
+    assert!(prompt.path.ends_with("/alert/text"));
+    assert_eq!(prompt.element_id, None);
+    assert_eq!(prompt.body, Some(json!({"text": "***"})));
+    assert!(!format!("{:?}", *events).contains("hunter2"));
+}
+
+#[test]
+fn files_cookies_and_private_keys_are_redacted() {
+    let mock = MockWebDriver::start();
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+    let tracing = TracingExecutor::new(Box::new(HttpClient::new(mock.url().to_string())))
+        .with_sink(Arc::new(move |event: &CommandEvent| sink.lock().unwrap().push(event.clone())));
+
+    let _ = tracing.post::<Value, _>("session/1/se/file", json!({"file": "UEsDBA=="}));
+    let _ = tracing.post::<Value, _>("session/1/cookie", json!({"cookie": {"name": "sid", "value": "s3cret"}}));
+    let credential = json!({"credentialId": "AQI", "privateKey": "MIGH", "rpId": "example.com"});
+    let _ = tracing.post::<Value, _>("session/1/webauthn/authenticator/a1/credential", credential);
+
+    let bodies: Vec<Value> = events.lock().unwrap().iter().map(|e| e.body.clone().unwrap()).collect();
+    assert_eq!(bodies, vec![
+        json!({"file": "***"}),
+        json!({"cookie": "***"}),
+        json!({"credentialId": "AQI", "privateKey": "***", "rpId": "example.com"}),
+    ]);
+}
+
+#[test]
+fn failures_and_durations_are_reported() {
+    let mock = MockWebDriver::start();
+    let (driver, events) = traced(&mock);
+    let session = driver.create_session(json!({"capabilities": {}}).as_object().unwrap().clone()).unwrap();
+    mock.enqueue("GET", "session/*/title", MockResponse::value(json!("Slow")).delayed(Duration::from_millis(50)));
+
+    assert_eq!(session.get_title().unwrap(), "Slow");
+    assert!(session.find_element_by(&By::css("missing")).is_err());
+
+    let events = events.lock().unwrap();
+    let title = &events[events.len() - 2];
+    assert!(title.duration >= Duration::from_millis(50));
+    assert_eq!(title.body, None);
+    assert_eq!(title.error, None);
+    let failed = events.last().unwrap();
+    assert_eq!(failed.error.as_deref(), Some("no such element"));
+    assert_eq!(failed.outcome(), "error");
+    assert_eq!(failed.body, Some(json!({"using": "css selector", "value": "missing"})));
+}
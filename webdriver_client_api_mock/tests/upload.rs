@@ -0,0 +1,88 @@
+extern crate base64;
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_simple_impl as simple_impl;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+extern crate zip;
+
+mod common;
+
+use common::connect;
+use mock::state::MockElement;
+use mock::MockResponse;
+use mock::MockWebDriver;
+use serde_json::json;
+use simplehttp::HttpClient;
+use wda::by::By;
+
+use std::fs;
+use std::io::Cursor;
+use std::io::Read;
+use std::path::PathBuf;
+
+fn file_input(mock: &MockWebDriver) {
+    mock.add_element("css selector", "input", MockElement::new("input").property("type", json!("file")));
+}
+
+fn temp_file(name: &str, content: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("wda-upload-{}-{}", std::process::id(), name));
+    fs::write(&path, content).unwrap();
+    fs::canonicalize(path).unwrap()
+}
+
+#[test]
+fn only_file_inputs_accept_uploads() {
+    let mock = MockWebDriver::start();
+    mock.add_element("css selector", "input", MockElement::new("input").property("type", json!("text")));
+    let session = connect(&mock);
+    let file = temp_file("text.txt", "a");
+
+    let input = session.find_element_by(&By::css("input")).unwrap();
+    let error = input.upload_file(&file).unwrap_err();
+    assert_eq!(error.error, "invalid argument");
+    assert!(!mock.requests().iter().any(|r| r.path.ends_with("/value")));
+    fs::remove_file(file).unwrap();
+}
+
+#[test]
+fn local_paths_are_joined_with_newlines() {
+    let mock = MockWebDriver::start();
+    file_input(&mock);
+    let session = connect(&mock);
+    let first = temp_file("first.txt", "1");
+    let second = temp_file("second.txt", "2");
+
+    let input = session.find_element_by(&By::css("input")).unwrap();
+    input.upload_files(&[&first, &second]).unwrap();
+    let text = format!("{}\n{}", first.display(), second.display());
+    assert_eq!(mock.last_request().unwrap().body, json!({"text": text}));
+    assert!(!mock.requests().iter().any(|r| r.path.ends_with("/se/file")));
+    fs::remove_file(first).unwrap();
+    fs::remove_file(second).unwrap();
+}
+
+#[test]
+fn remote_uploads_send_the_zipped_file_first() {
+    let mock = MockWebDriver::start();
+    file_input(&mock);
+    // A localhost URL may still be a driver in a container or behind a tunnel
+    let http = HttpClient::builder(mock.url().to_string()).local(false).build().unwrap();
+    let session = common::connect_with(http, json!({"capabilities": {}}));
+    let file = temp_file("report.csv", "a,b\n1,2\n");
+    mock.enqueue("POST", "session/*/se/file", MockResponse::value(json!("/tmp/upload123/report.csv")));
+
+    let input = session.find_element_by(&By::css("input")).unwrap();
+    input.upload_file(&file).unwrap();
+    let requests = mock.requests();
+    let transfer = &requests[requests.len() - 2];
+    assert!(transfer.path.ends_with("/se/file"));
+    let archive = base64::decode(transfer.body["file"].as_str().unwrap()).unwrap();
+    let mut zip = zip::ZipArchive::new(Cursor::new(archive)).unwrap();
+    let mut entry = zip.by_name(&file.file_name().unwrap().to_string_lossy()).unwrap();
+    let mut content = String::new();
+    entry.read_to_string(&mut content).unwrap();
+    assert_eq!(content, "a,b\n1,2\n");
+    assert_eq!(mock.last_request().unwrap().body, json!({"text": "/tmp/upload123/report.csv"}));
+    fs::remove_file(file).unwrap();
+}
@@ -0,0 +1,83 @@
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_simple_impl as simple_impl;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+
+mod common;
+
+use common::connect;
+use mock::MockWebDriver;
+use serde_json::json;
+use wda::webauthn::base64url;
+use wda::webauthn::Credential;
+use wda::webauthn::Protocol;
+use wda::webauthn::Transport;
+use wda::webauthn::VirtualAuthenticatorOptions;
+
+#[test]
+fn authenticators_are_added_with_typed_options() {
+    let mock = MockWebDriver::start();
+    let session = connect(&mock);
+    let options = VirtualAuthenticatorOptions::new()
+        .protocol(Protocol::Ctap21)
+        .transport(Transport::Internal)
+        .resident_key(true)
+        .user_verification(true)
+        .user_verified(true);
+
+    let authenticator = session.add_virtual_authenticator(&options).unwrap();
+    assert_eq!(mock.last_request().unwrap().body, json!({
+        "protocol": "ctap2_1",
+        "transport": "internal",
+        "hasResidentKey": true,
+        "hasUserVerification": true,
+        "isUserConsenting": true,
+        "isUserVerified": true,
+    }));
+
+    authenticator.set_user_verified(false).unwrap();
+    let stored = mock.state().sessions[session.get_session_id()].authenticators[authenticator.id()].clone();
+    assert_eq!(stored.options["isUserVerified"], json!(false));
+
+    let id = authenticator.id().to_string();
+    authenticator.remove().unwrap();
+    assert!(mock.state().sessions[session.get_session_id()].authenticators.is_empty());
+    assert!(mock.last_request().unwrap().path.ends_with(&format!("webauthn/authenticator/{}", id)));
+}
+
+#[test]
+fn credentials_round_trip_as_base64url() {
+    let mock = MockWebDriver::start();
+    let session = connect(&mock);
+    let authenticator = session.add_virtual_authenticator(&VirtualAuthenticatorOptions::new().resident_key(true)).unwrap();
+    let passkey = Credential::resident(&[0xfb, 0xff, 0x01], "example.com", b"pkcs8", b"user-1", 7);
+    let legacy = Credential::non_resident(&[2], "example.com", b"key", 0);
+
+    authenticator.add_credential(&passkey).unwrap();
+    assert_eq!(mock.last_request().unwrap().body, json!({
+        "credentialId": "-_8B",
+        "isResidentCredential": true,
+        "rpId": "example.com",
+        "privateKey": "cGtjczg",
+        "userHandle": "dXNlci0x",
+        "signCount": 7,
+    }));
+    authenticator.add_credential(&legacy).unwrap();
+    assert!(mock.last_request().unwrap().body.get("userHandle").is_none());
+    assert_eq!(authenticator.get_credentials().unwrap(), vec![passkey.clone(), legacy]);
+
+    authenticator.remove_credential(&[2]).unwrap();
+    assert_eq!(authenticator.get_credentials().unwrap(), vec![passkey]);
+    assert_eq!(authenticator.remove_credential(&[2]).unwrap_err().error, "invalid argument");
+
+    authenticator.remove_all_credentials().unwrap();
+    assert!(authenticator.get_credentials().unwrap().is_empty());
+}
+
+#[test]
+fn base64url_tolerates_padding() {
+    assert_eq!(base64url::encode(b"user-1"), "dXNlci0x");
+    assert_eq!(base64url::decode("cGtjczg=").unwrap(), b"pkcs8");
+    assert_eq!(base64url::decode("-_8B").unwrap(), vec![0xfb, 0xff, 0x01]);
+}
@@ -0,0 +1,70 @@
+use serde_json::Map;
+use serde_json::Value;
+
+use wda::chromium::ChromiumSession;
+use wda::chromium::NetworkConditions;
+use wda::structs::WebDriverError;
+use wda::traits::HttpExecutor;
+
+use crate::DefaultSession;
+
+fn sink(sink_name: &str) -> Map<String, Value> {
+    let mut body = Map::new();
+    body.insert("sinkName".to_string(), Value::String(sink_name.to_string()));
+    body
+}
+
+impl<H: HttpExecutor + 'static> ChromiumSession<H> for DefaultSession<H> {
+    fn execute_cdp(&self, method: &str, params: Value) -> Result<Value, WebDriverError> {
+        let mut body = Map::new();
+        body.insert("cmd".to_string(), Value::String(method.to_string()));
+        body.insert("params".to_string(), params);
+        self.http.post(format!("{}/cdp/execute", self.vendor_prefix()).as_str(), body)
+    }
+
+    fn get_network_conditions(&self) -> Result<NetworkConditions, WebDriverError> {
+        self.http.get("chromium/network_conditions")
+    }
+
+    fn set_network_conditions(&self, conditions: &NetworkConditions) -> Result<(), WebDriverError> {
+        let conditions = serde_json::to_value(conditions)
+            .map_err(|e| WebDriverError::new("invalid argument", &e.to_string()))?;
+        let mut body = Map::new();
+        body.insert("network_conditions".to_string(), conditions);
+        self.http.post("chromium/network_conditions", body)
+    }
+
+    fn delete_network_conditions(&self) -> Result<(), WebDriverError> {
+        self.http.delete("chromium/network_conditions")
+    }
+
+    fn get_cast_sinks(&self) -> Result<Vec<Value>, WebDriverError> {
+        self.http.get(format!("{}/cast/get_sinks", self.vendor_prefix()).as_str())
+    }
+
+    fn set_cast_sink_to_use(&self, sink_name: &str) -> Result<(), WebDriverError> {
+        self.http.post(format!("{}/cast/set_sink_to_use", self.vendor_prefix()).as_str(), sink(sink_name))
+    }
+
+    fn start_tab_mirroring(&self, sink_name: &str) -> Result<(), WebDriverError> {
+        self.http.post(format!("{}/cast/start_tab_mirroring", self.vendor_prefix()).as_str(), sink(sink_name))
+    }
+
+    fn start_desktop_mirroring(&self, sink_name: &str) -> Result<(), WebDriverError> {
+        self.http.post(format!("{}/cast/start_desktop_mirroring", self.vendor_prefix()).as_str(), sink(sink_name))
+    }
+
+    fn stop_casting(&self, sink_name: &str) -> Result<(), WebDriverError> {
+        self.http.post(format!("{}/cast/stop_casting", self.vendor_prefix()).as_str(), sink(sink_name))
+    }
+
+    fn get_cast_issue_message(&self) -> Result<String, WebDriverError> {
+        self.http.get(format!("{}/cast/get_issue_message", self.vendor_prefix()).as_str())
+    }
+
+    fn launch_app(&self, id: &str) -> Result<(), WebDriverError> {
+        let mut body = Map::new();
+        body.insert("id".to_string(), Value::String(id.to_string()));
+        self.http.post("chromium/launch_app", body)
+    }
+}
@@ -0,0 +1,47 @@
+use serde_json::Map;
+use serde_json::Value;
+
+use std::fs;
+use std::path::Path;
+
+use wda::firefox::FirefoxContext;
+use wda::firefox::FirefoxSession;
+use wda::structs::WebDriverError;
+use wda::traits::HttpExecutor;
+
+use crate::DefaultSession;
+
+impl<H: HttpExecutor + 'static> FirefoxSession<H> for DefaultSession<H> {
+    fn install_addon(&self, path: &Path, temporary: bool) -> Result<String, WebDriverError> {
+        let addon = fs::read(path).map_err(|e| WebDriverError::new(
+            "invalid argument",
+            &format!("Cannot read add-on {}: {}", path.display(), e),
+        ))?;
+        let mut body = Map::new();
+        body.insert("addon".to_string(), Value::String(base64::encode(&addon)));
+        body.insert("temporary".to_string(), Value::Bool(temporary));
+        self.http.post("moz/addon/install", body)
+    }
+
+    fn uninstall_addon(&self, id: &str) -> Result<(), WebDriverError> {
+        let mut body = Map::new();
+        body.insert("id".to_string(), Value::String(id.to_string()));
+        self.http.post("moz/addon/uninstall", body)
+    }
+
+    fn get_context(&self) -> Result<FirefoxContext, WebDriverError> {
+        self.http.get("moz/context")
+    }
+
+    fn set_context(&self, context: FirefoxContext) -> Result<(), WebDriverError> {
+        let context = serde_json::to_value(context)
+            .map_err(|e| WebDriverError::new("invalid argument", &e.to_string()))?;
+        let mut body = Map::new();
+        body.insert("context".to_string(), context);
+        self.http.post("moz/context", body)
+    }
+
+    fn full_page_screenshot(&self) -> Result<String, WebDriverError> {
+        self.http.get("moz/screenshot/full")
+    }
+}
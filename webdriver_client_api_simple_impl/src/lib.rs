@@ -1,10 +1,25 @@
+extern crate base64;
 extern crate log;
 extern crate webdriver_client_api as wda;
+extern crate zip;
+
+mod chromium;
+mod firefox;
 
 use log::*;
+use std::fs;
+use std::io::Cursor;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::RwLock;
+use std::time::Duration;
 use serde_json::Map;
 use serde_json::Value;
 
+use wda::alert::Alert;
 use wda::traits::HttpExecutor;
 use wda::traits::Element;
 use wda::httpdecorator::HttpExecutorPathDecorator;
@@ -14,127 +29,202 @@ use wda::structs::Frame::Elem;
 use wda::structs::Frame::Id;
 use wda::structs::Status;
 use wda::structs::NewSession;
+use wda::structs::PermissionDescriptor;
+use wda::structs::PermissionState;
 use wda::structs::WebDriverError;
 use wda::structs::Frame;
-use wda::structs::Rect;
+use wda::structs::ElementRect;
+use wda::structs::SetWindowRect;
+use wda::structs::WindowRect;
 use wda::traits::WebDriver;
 use wda::traits::Session;
+use wda::traits::SessionHandle;
+use wda::traits::ElementHandle;
 use wda::structs::Timeouts;
 use wda::structs::ExecuteScript;
 use wda::structs::Cookies;
+use wda::structs::Capabilities;
+use wda::grid::Grid;
+use wda::grid::GridStatus;
+use wda::keys;
+use wda::logs::LogCapture;
+use wda::webauthn::VirtualAuthenticator;
+use wda::webauthn::VirtualAuthenticatorOptions;
+
+const STALE_ELEMENT_REFERENCE: &str = "stale element reference";
 
 impl<H: HttpExecutor + 'static> Element<H> for DefaultElement<H> {
-    fn get_reference_id(&self) -> &str {
-        self.ref_id.as_str()
+    fn get_reference_id(&self) -> String {
+        self.current().ref_id
     }
 
-    fn get_http_executor(self) -> Box<HttpExecutorPathDecorator<HttpExecutorPathDecorator<H>>> {
-        self.http
+    fn get_http_executor(&self) -> Arc<HttpExecutorPathDecorator<HttpExecutorPathDecorator<H>>> {
+        self.current().http
     }
 
+    fn relocating(&self, enabled: bool) -> ElementHandle<H> {
+        Arc::new(DefaultElement { relocate: enabled, ..self.clone() })
+    }
 
-    fn find_element(&self, using: &dyn LocatorStrategy, value: &str) -> Result<Box<dyn Element<H>>, WebDriverError> {
-        let mut request = Map::new();
-        request.insert("using".to_string(), Value::String(using.get_using_str().to_string()));
-        request.insert("value".to_string(), Value::String(value.to_string()));
-
-
-        let parent_executor = self.clone().http.clone();
-
-        //TODO handle exception
-        self.http.post::<Value, Map<String, Value>>("element", request).map(|v| Box::new(
-            DefaultElement::<H>::new(
-                parent_executor.into_inner(),
-                v.get(WEB_ELEMENT_IDENTIFIER)
-                    .unwrap()
-                    .as_str()
-                    .unwrap()
-                    .to_string(),
-            )
-        ) as Box<dyn Element<H>>)
+    fn replaying(&self, enabled: bool) -> ElementHandle<H> {
+        Arc::new(DefaultElement { replay: enabled, ..self.clone() })
     }
 
-    fn find_elements(&self, using: &dyn LocatorStrategy, value: &str) -> Result<Vec<Box<dyn Element<H>>>, WebDriverError> {
-        let mut request = Map::new();
-        request.insert("using".to_string(), Value::String(using.get_using_str().to_string()));
-        request.insert("value".to_string(), Value::String(value.to_string()));
-
-        //TODO handle exception
-        self.http.post::<Value, Map<String, Value>>("elements", request)
-            .map(move |v| {
-                let vec: Vec<Box<dyn Element<H>>> = v.get(WEB_ELEMENT_IDENTIFIER).unwrap()
-                    .as_array().unwrap()
-                    .into_iter()
-                    .map(move |e|
-                        Box::new(
-                            DefaultElement::<H>::new(
-                                (self.clone().http.clone()).into_inner(),
-                                e.as_str().unwrap().to_string())
-                        ) as Box<dyn Element<H>>
-                    ).collect();
-                vec
-            }
-            )
+    fn find_element(&self, using: &dyn LocatorStrategy, value: &str) -> Result<ElementHandle<H>, WebDriverError> {
+        let origin = Origin {
+            parent: Some(self.clone()),
+            using: using.get_using_str().to_string(),
+            value: value.to_string(),
+            index: None,
+        };
+        let ref_id = self.command(|http| origin.locate(http))?;
+        Ok(Arc::new(DefaultElement::located(self.session.clone(), ref_id, Some(origin))))
+    }
+
+    fn find_elements(&self, using: &dyn LocatorStrategy, value: &str) -> Result<Vec<ElementHandle<H>>, WebDriverError> {
+        let ref_ids = self.command(|http| http.post::<Value, _>("elements", locator(using.get_using_str(), value))
+            .and_then(|v| element_refs(&v)))?;
+        Ok(ref_ids.into_iter()
+            .enumerate()
+            .map(|(index, ref_id)| {
+                let origin = Origin {
+                    parent: Some(self.clone()),
+                    using: using.get_using_str().to_string(),
+                    value: value.to_string(),
+                    index: Some(index),
+                };
+                Arc::new(DefaultElement::located(self.session.clone(), ref_id, Some(origin))) as ElementHandle<H>
+            })
+            .collect())
     }
 
     fn is_selected(&self) -> Result<bool, WebDriverError> {
-        self.http.get("selected")
+        self.command(|http| http.get("selected"))
     }
 
     fn get_attribute(&self, name: &str) -> Result<String, WebDriverError> {
-        self.http.get(format!("attribute/{}", name).as_str())
+        self.command(|http| http.get(format!("attribute/{}", name).as_str()))
     }
 
     fn get_property(&self, name: &str) -> Result<String, WebDriverError> {
-        self.http.get(format!("property/{}", name).as_str())
+        self.command(|http| http.get(format!("property/{}", name).as_str()))
     }
 
     fn get_css_value(&self, name: &str) -> Result<String, WebDriverError> {
-        self.http.get(format!("css/{}", name).as_str())
+        self.command(|http| http.get(format!("css/{}", name).as_str()))
     }
 
     fn get_text(&self) -> Result<String, WebDriverError> {
-        self.http.get("text")
+        self.command(|http| http.get("text"))
     }
 
     fn get_tag_name(&self) -> Result<String, WebDriverError> {
-        self.http.get("name")
+        self.command(|http| http.get("name"))
     }
 
-    fn get_rect(&self) -> Result<Rect, WebDriverError> {
-        self.http.get("rect")
+    fn get_rect(&self) -> Result<ElementRect, WebDriverError> {
+        self.command(|http| http.get("rect"))
     }
 
     fn is_enabled(&self) -> Result<bool, WebDriverError> {
-        self.http.get("enabled")
+        self.command(|http| http.get("enabled"))
     }
 
     fn click(&self) -> Result<(), WebDriverError> {
-        self.http.post("click", Value::Object(Map::new()))
+        self.action(|http| http.post("click", Value::Object(Map::new())))
     }
 
     fn clear(&self) -> Result<(), WebDriverError> {
-        self.http.post("clear", Value::Object(Map::new()))
+        self.action(|http| http.post("clear", Value::Object(Map::new())))
     }
 
     fn send_keys(&self, text: &str) -> Result<(), WebDriverError> {
+        keys::validate(text)?;
         let mut request = Map::new();
         request.insert("text".to_string(), Value::String(text.to_string()));
-        self.http.post("value", request)
+        self.action(|http| http.post("value", &request))
     }
 
     fn take_screenshot(&self) -> Result<String, WebDriverError> {
-        self.http.get("screenshot")
+        self.command(|http| http.get("screenshot"))
+    }
+
+    fn upload_files(&self, paths: &[&Path]) -> Result<(), WebDriverError> {
+        if !self.get_tag_name()?.eq_ignore_ascii_case("input")
+            || !self.get_property("type")?.eq_ignore_ascii_case("file") {
+            return Err(WebDriverError::new("invalid argument", "Element is not an <input type=file>"));
+        }
+        let mut files = Vec::new();
+        for path in paths {
+            let absolute = fs::canonicalize(path).map_err(|e| WebDriverError::new(
+                "invalid argument",
+                &format!("Cannot resolve {}: {}", path.display(), e),
+            ))?;
+            if self.session.is_local() {
+                files.push(absolute.to_string_lossy().into_owned());
+            } else {
+                files.push(self.transfer_file(&absolute)?);
+            }
+        }
+        self.send_keys(&files.join("\n"))
+    }
+}
+
+impl<I: HttpExecutor> DefaultElement<I> {
+    /// Sends the file zipped and base64-encoded to the Selenium `se/file` endpoint,
+    /// returning the path of its copy on the remote end.
+    fn transfer_file(&self, path: &Path) -> Result<String, WebDriverError> {
+        let io_error = |e: &dyn std::fmt::Display| WebDriverError::new(
+            "invalid argument",
+            &format!("Cannot prepare {} for upload: {}", path.display(), e),
+        );
+        let content = fs::read(path).map_err(|e| io_error(&e))?;
+        let name = path.file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file(name, zip::write::FileOptions::default()).map_err(|e| io_error(&e))?;
+        zip.write_all(&content).map_err(|e| io_error(&e))?;
+        let archive = zip.finish().map_err(|e| io_error(&e))?.into_inner();
+
+        let mut body = Map::new();
+        body.insert("file".to_string(), Value::String(base64::encode(&archive)));
+        debug!("Transferring {} to remote end", path.display());
+        self.session.post("se/file", body)
     }
 }
 
+fn element_ref(value: &Value) -> Result<String, WebDriverError> {
+    wda::element_reference(value)
+        .map(str::to_string)
+        .ok_or_else(|| WebDriverError::new("unknown error", &format!("Expected a web element, got {}", value)))
+}
+
+fn frame_element(ref_id: &str) -> Value {
+    let mut elem_repr = Map::new();
+    elem_repr.insert(WEB_ELEMENT_IDENTIFIER.to_string(), Value::String(ref_id.to_string()));
+    Value::Object(elem_repr)
+}
+
+fn element_refs(value: &Value) -> Result<Vec<String>, WebDriverError> {
+    value.as_array()
+        .ok_or_else(|| WebDriverError::new("unknown error", &format!("Expected a list of web elements, got {}", value)))?
+        .iter()
+        .map(element_ref)
+        .collect()
+}
+
 
 impl<H: HttpExecutor + 'static> Session<H> for DefaultSession<H> {
-    fn get_session_id(self) -> String {
-        self.session_id
+    fn get_session_id(&self) -> &str {
+        self.session_id.as_str()
     }
-    fn get_http_executor(self) -> Box<HttpExecutorPathDecorator<H>> {
-        self.http
+    fn get_capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+    fn get_http_executor(&self) -> &HttpExecutorPathDecorator<H> {
+        &self.http
     }
 
     fn delete_session(&self) -> Result<(), WebDriverError> {
@@ -152,7 +242,9 @@ impl<H: HttpExecutor + 'static> Session<H> for DefaultSession<H> {
     fn navigate_to(&self, url: &str) -> Result<(), WebDriverError> {
         let mut body = Map::new();
         body.insert("url".to_string(), Value::String(url.to_string()));
-        self.http.post("url", body)
+        self.http.post::<(), _>("url", body)?;
+        self.frames().clear();
+        Ok(())
     }
 
     fn get_current_url(&self) -> Result<String, WebDriverError> {
@@ -160,15 +252,21 @@ impl<H: HttpExecutor + 'static> Session<H> for DefaultSession<H> {
     }
 
     fn back(&self) -> Result<(), WebDriverError> {
-        self.http.post("back", Value::Object(Map::new()))
+        self.http.post::<(), _>("back", Value::Object(Map::new()))?;
+        self.frames().clear();
+        Ok(())
     }
 
     fn forward(&self) -> Result<(), WebDriverError> {
-        self.http.post("forward", Value::Object(Map::new()))
+        self.http.post::<(), _>("forward", Value::Object(Map::new()))?;
+        self.frames().clear();
+        Ok(())
     }
 
     fn refresh(&self) -> Result<(), WebDriverError> {
-        self.http.post("refresh", Value::Object(Map::new()))
+        self.http.post::<(), _>("refresh", Value::Object(Map::new()))?;
+        self.frames().clear();
+        Ok(())
     }
 
     fn get_title(&self) -> Result<String, WebDriverError> {
@@ -180,118 +278,104 @@ impl<H: HttpExecutor + 'static> Session<H> for DefaultSession<H> {
     }
 
     fn close_window(&self) -> Result<Vec<String>, WebDriverError> {
-        self.http.delete("window")
+        let handles = self.http.delete("window")?;
+        self.frames().clear();
+        Ok(handles)
     }
 
     fn switch_to_window(&self, handle: &str) -> Result<(), WebDriverError> {
         let mut body = Map::new();
         body.insert("handle".to_string(), Value::String(handle.to_string()));
-        self.http.post("window", body)
+        self.http.post::<(), _>("window", body)?;
+        self.frames().clear();
+        Ok(())
     }
 
     fn get_window_handles(&self) -> Result<Vec<String>, WebDriverError> {
         self.http.get("window/handles")
     }
 
-    fn switch_to_frame(&self, frame: Frame) -> Result<(), WebDriverError> {
+    fn switch_to_frame(&self, frame: Frame<H>) -> Result<(), WebDriverError> {
         let mut body = Map::new();
-        let id :Value = match frame {
-            Elem(e) => {
-                let mut elem_repr = Map::new();
-                elem_repr.insert(WEB_ELEMENT_IDENTIFIER.to_string(), Value::String(e));
-                Value::Object(elem_repr)
-            },
-            Id(n) => Value::from(n),
+        let id :Value = match &frame {
+            Elem(e) => frame_element(e),
+            Frame::Element(e) => frame_element(&e.get_reference_id()),
+            Frame::Locator(by) => return self.switch_to_frame(Frame::Element(self.find_element_by(by)?)),
+            Id(n) => Value::from(*n),
             Frame::None => Value::Null
         };
         body.insert("id".to_string(), id);
-        self.http.post("frame", Value::Object(body))
+        self.http.post::<(), _>("frame", Value::Object(body))?;
+        let mut frames = self.frames();
+        match frame {
+            Frame::None => frames.clear(),
+            frame => frames.push(frame),
+        }
+        Ok(())
     }
 
     fn switch_to_parent(&self) -> Result<(), WebDriverError> {
-        self.http.post("frame/parent", Value::Object(Map::new()))
+        self.http.post::<(), _>("frame/parent", Value::Object(Map::new()))?;
+        self.frames().pop();
+        Ok(())
+    }
+
+    fn get_frame_path(&self) -> Vec<Frame<H>> {
+        self.frames().clone()
     }
 
-    fn get_window_rect(&self) -> Result<Rect, WebDriverError> {
+    fn get_window_rect(&self) -> Result<WindowRect, WebDriverError> {
         self.http.get("window/rect")
     }
 
-    fn set_window_rect(&self, rect: Rect) -> Result<Rect, WebDriverError> {
+    fn set_window_rect(&self, rect: SetWindowRect) -> Result<WindowRect, WebDriverError> {
         self.http.post("window/rect", rect)
     }
 
-    fn maximize(&self) -> Result<Rect, WebDriverError> {
+    fn maximize(&self) -> Result<WindowRect, WebDriverError> {
         self.http.post("window/maximize", Value::Object(Map::new()))
     }
 
-    fn minimize(&self) -> Result<Rect, WebDriverError> {
+    fn minimize(&self) -> Result<WindowRect, WebDriverError> {
         self.http.post("window/minimize", Value::Object(Map::new()))
     }
 
-    fn fullscreen(&self) -> Result<Rect, WebDriverError> {
+    fn fullscreen(&self) -> Result<WindowRect, WebDriverError> {
         self.http.post("window/fullscreen", Value::Object(Map::new()))
     }
 
-    fn get_active_element(&self) -> Result<Box<dyn Element<H>>, WebDriverError> {
-        let parent_executor = self.clone().get_http_executor();
-
-        //TODO handle exception
-
-        self.http.get::<Value>("element/active").map(|v| Box::new(
-            DefaultElement::<H>::new(
-                parent_executor,
-                v.get(WEB_ELEMENT_IDENTIFIER)
-                    .unwrap()
-                    .as_str()
-                    .unwrap()
-                    .to_string(),
-            )
-        ) as Box<dyn Element<H>>)
-    }
-
-    fn find_element(&self, using: &dyn LocatorStrategy, value: &str) -> Result<Box<dyn Element<H>>, WebDriverError> {
-        let mut request = Map::new();
-        request.insert("using".to_string(), Value::String(using.get_using_str().to_string()));
-        request.insert("value".to_string(), Value::String(value.to_string()));
-
-
-        let parent_executor = self.clone().get_http_executor();
-
-        //TODO handle exception
-        self.http.post::<Value, Map<String, Value>>("element", request).map(|v| Box::new(
-            DefaultElement::<H>::new(
-                parent_executor,
-                v.get(WEB_ELEMENT_IDENTIFIER)
-                    .unwrap()
-                    .as_str()
-                    .unwrap()
-                    .to_string(),
-            )
-        ) as Box<dyn Element<H>>)
+    fn get_active_element(&self) -> Result<ElementHandle<H>, WebDriverError> {
+        self.http.get::<Value>("element/active")
+            .and_then(|v| element_ref(&v))
+            .map(|id| Arc::new(DefaultElement::<H>::new(self.http.clone(), id)) as ElementHandle<H>)
     }
 
-    fn find_elements(&self, using: &dyn LocatorStrategy, value: &str) -> Result<Vec<Box<dyn Element<H>>>, WebDriverError> {
-        let mut request = Map::new();
-        request.insert("using".to_string(), Value::String(using.get_using_str().to_string()));
-        request.insert("value".to_string(), Value::String(value.to_string()));
-
-
-        //TODO handle exception
-        self.http.post::<Value, Map<String, Value>>("elements", request)
-            .map(move |v| {
-                let vec: Vec<Box<dyn Element<H>>> = v.get(WEB_ELEMENT_IDENTIFIER).unwrap()
-                    .as_array().unwrap()
-                    .iter()
-                    .map(move |e|
-                        Box::new(
-                            DefaultElement::<H>::new(
-                                self.clone().get_http_executor(),
-                                e.as_str().unwrap().to_string())
-                        ) as Box<dyn Element<H>>
-                    ).collect();
-                vec
-            }
-            )
+    fn find_element(&self, using: &dyn LocatorStrategy, value: &str) -> Result<ElementHandle<H>, WebDriverError> {
+        let origin = Origin {
+            parent: None,
+            using: using.get_using_str().to_string(),
+            value: value.to_string(),
+            index: None,
+        };
+        let ref_id = origin.locate(self.http.as_ref())?;
+        Ok(Arc::new(DefaultElement::located(self.http.clone(), ref_id, Some(origin))))
+    }
+
+    fn find_elements(&self, using: &dyn LocatorStrategy, value: &str) -> Result<Vec<ElementHandle<H>>, WebDriverError> {
+        let ref_ids = self.http.post::<Value, _>("elements", locator(using.get_using_str(), value))
+            .and_then(|v| element_refs(&v))?;
+        Ok(ref_ids.into_iter()
+            .enumerate()
+            .map(|(index, ref_id)| {
+                let origin = Origin {
+                    parent: None,
+                    using: using.get_using_str().to_string(),
+                    value: value.to_string(),
+                    index: Some(index),
+                };
+                Arc::new(DefaultElement::located(self.http.clone(), ref_id, Some(origin))) as ElementHandle<H>
+            })
+            .collect())
     }
 
     fn get_page_source(&self) -> Result<String, WebDriverError> {
@@ -352,74 +436,212 @@ impl<H: HttpExecutor + 'static> Session<H> for DefaultSession<H> {
         self.http.post("alert/text", map)
     }
 
+    fn alert(&self) -> Result<Alert<'_, H>, WebDriverError> {
+        Alert::open(self)
+    }
+
+    fn wait_for_alert(&self, timeout: Duration) -> Result<Alert<'_, H>, WebDriverError> {
+        Alert::wait(self, timeout)
+    }
+
     fn take_screenshot(&self) -> Result<String, WebDriverError> {
         self.http.get("screenshot")
     }
+
+    fn set_permission(&self, descriptor: &PermissionDescriptor, state: PermissionState) -> Result<(), WebDriverError> {
+        let mut body = Map::new();
+        body.insert("descriptor".to_string(), serde_json::to_value(descriptor)
+            .map_err(|e| WebDriverError::new("invalid argument", &e.to_string()))?);
+        body.insert("state".to_string(), serde_json::to_value(state)
+            .map_err(|e| WebDriverError::new("invalid argument", &e.to_string()))?);
+        self.http.post("permissions", body)
+    }
+
+    fn add_virtual_authenticator(
+        &self,
+        options: &VirtualAuthenticatorOptions,
+    ) -> Result<VirtualAuthenticator<'_, H>, WebDriverError> {
+        VirtualAuthenticator::add(self, options)
+    }
+
+    fn capture_logs(&self) -> Result<LogCapture<'_, H>, WebDriverError> {
+        LogCapture::start(self)
+    }
 }
 
+/// Element found through a session; commands failing with a stale element reference are
+/// retried once after finding the element again through its `Origin`, unless disabled.
+/// State-changing commands are only retried if opted in with `replaying`.
+#[derive(Clone)]
 struct DefaultElement<I: HttpExecutor> {
-    http: Box<HttpExecutorPathDecorator<HttpExecutorPathDecorator<I>>>,
+    session: Arc<HttpExecutorPathDecorator<I>>,
+    current: Arc<RwLock<Located<I>>>,
+    origin: Option<Arc<Origin<I>>>,
+    relocate: bool,
+    replay: bool,
+}
+
+#[derive(Clone)]
+struct Located<I: HttpExecutor> {
     ref_id: String,
+    http: Arc<HttpExecutorPathDecorator<HttpExecutorPathDecorator<I>>>,
+}
+
+/// How an element was found, so that it can be found again once stale.
+struct Origin<I: HttpExecutor> {
+    /// Element searched from, `None` for the document.
+    parent: Option<DefaultElement<I>>,
+    using: String,
+    value: String,
+    /// Position among the results of `elements`, `None` if found with `element`.
+    index: Option<usize>,
 }
 
 impl<I: HttpExecutor> DefaultElement<I> {
-    pub fn new(http: Box<HttpExecutorPathDecorator<I>>, ref_id: String) -> Self {
+    pub fn new(session: Arc<HttpExecutorPathDecorator<I>>, ref_id: String) -> Self {
+        DefaultElement::located(session, ref_id, None)
+    }
+
+    fn located(session: Arc<HttpExecutorPathDecorator<I>>, ref_id: String, origin: Option<Origin<I>>) -> Self {
         DefaultElement {
-            http: Box::new(
-                HttpExecutorPathDecorator::<HttpExecutorPathDecorator<I>>::new(
-                    http,
-                    format!("element/{}", ref_id))
-            ),
+            current: Arc::new(RwLock::new(Located::new(&session, ref_id))),
+            session,
+            origin: origin.map(Arc::new),
+            relocate: true,
+            replay: false,
+        }
+    }
+
+    fn current(&self) -> Located<I> {
+        self.current.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn command<T, F>(&self, command: F) -> Result<T, WebDriverError>
+        where F: Fn(&HttpExecutorPathDecorator<HttpExecutorPathDecorator<I>>) -> Result<T, WebDriverError> {
+        self.run(self.relocate, command)
+    }
+
+    /// Like `command` for commands with side effects, which are only replayed if opted in.
+    fn action<T, F>(&self, command: F) -> Result<T, WebDriverError>
+        where F: Fn(&HttpExecutorPathDecorator<HttpExecutorPathDecorator<I>>) -> Result<T, WebDriverError> {
+        self.run(self.relocate && self.replay, command)
+    }
+
+    fn run<T, F>(&self, replay: bool, command: F) -> Result<T, WebDriverError>
+        where F: Fn(&HttpExecutorPathDecorator<HttpExecutorPathDecorator<I>>) -> Result<T, WebDriverError> {
+        match command(&self.current().http) {
+            Err(ref e) if e.error == STALE_ELEMENT_REFERENCE && replay && self.origin.is_some() => {
+                self.relocate()?;
+                command(&self.current().http)
+            }
+            result => result,
+        }
+    }
+
+    fn relocate(&self) -> Result<(), WebDriverError> {
+        let origin = self.origin.as_ref().expect("only found elements are relocated");
+        let ref_id = match &origin.parent {
+            Some(parent) => parent.command(|http| origin.locate(http))?,
+            None => origin.locate(self.session.as_ref())?,
+        };
+        debug!("Relocated stale element {} as {} by {} {}", self.current().ref_id, ref_id, origin.using, origin.value);
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = Located::new(&self.session, ref_id);
+        Ok(())
+    }
+}
+
+impl<I: HttpExecutor> Located<I> {
+    fn new(session: &Arc<HttpExecutorPathDecorator<I>>, ref_id: String) -> Self {
+        Located {
+            http: Arc::new(HttpExecutorPathDecorator::new(session.clone(), format!("element/{}", ref_id))),
             ref_id,
         }
     }
 }
 
+impl<I: HttpExecutor> Origin<I> {
+    /// Reference id of the element this locator currently finds below `http`.
+    fn locate<E: HttpExecutor>(&self, http: &E) -> Result<String, WebDriverError> {
+        let request = locator(&self.using, &self.value);
+        match self.index {
+            None => http.post::<Value, _>("element", request).and_then(|v| element_ref(&v)),
+            Some(index) => http.post::<Value, _>("elements", request)
+                .and_then(|v| element_refs(&v))?
+                .into_iter()
+                .nth(index)
+                .ok_or_else(|| WebDriverError::new(
+                    "no such element",
+                    &format!("Element {} of {} {} is gone", index, self.using, self.value),
+                )),
+        }
+    }
+}
+
+fn locator(using: &str, value: &str) -> Map<String, Value> {
+    let mut request = Map::new();
+    request.insert("using".to_string(), Value::String(using.to_string()));
+    request.insert("value".to_string(), Value::String(value.to_string()));
+    request
+}
+
 #[derive(Clone)]
 pub struct DefaultSession<I: HttpExecutor> {
-    http: Box<HttpExecutorPathDecorator<I>>,
+    http: Arc<HttpExecutorPathDecorator<I>>,
     session_id: String,
+    capabilities: Capabilities,
+    // Shared by clones, which drive the same remote session
+    frames: Arc<Mutex<Vec<Frame<I>>>>,
 }
 
 impl<I: HttpExecutor> DefaultSession<I> {
-    pub fn new(http: Box<I>, session_id: String) -> Self {
+    pub fn new(http: Arc<I>, session_id: String, capabilities: Capabilities) -> Self {
         DefaultSession {
-            http: Box::new(
+            http: Arc::new(
                 HttpExecutorPathDecorator::<I>::new(
                     http,
                     format!("session/{}", session_id))
             ),
             session_id,
+            capabilities,
+            frames: Arc::new(Mutex::new(Vec::new())),
         }
     }
+
+    fn frames(&self) -> MutexGuard<'_, Vec<Frame<I>>> {
+        self.frames.lock().unwrap_or_else(|e| e.into_inner())
+    }
 }
 
 
-struct DefaultWebDriver<H: HttpExecutor> {
-    http: Box<H>
+#[derive(Clone)]
+pub struct DefaultWebDriver<H: HttpExecutor> {
+    http: Arc<H>
 }
 
 impl<H: HttpExecutor> DefaultWebDriver<H> {
     pub fn new(facade: Box<H>) -> Self {
         DefaultWebDriver {
-            http: facade
+            http: Arc::from(facade)
         }
     }
+
+    /// Like `WebDriver::create_session`, but keeps the concrete type for vendor extension traits.
+    pub fn create_default_session(&self, caps: Map<String, Value>) -> Result<DefaultSession<H>, WebDriverError> {
+        self.http.post::<NewSession, Value>("session", Value::Object(caps))
+            .map(|ns| DefaultSession::<H>::new(self.http.clone(), ns.session_id, ns.capabilities))
+    }
 }
 
 
 //TODO попробовать убрать статик
 impl<H: 'static + HttpExecutor> WebDriver<H> for DefaultWebDriver<H> {
-    fn create_session(self, caps: Map<String, Value>) -> Result<Box<dyn Session<H>>, WebDriverError> {
-        self.http.post::<NewSession, Value>("session", Value::Object(caps))
-            .map(|ns|
-                Box::new(DefaultSession::<H>::new(self.get_http_executor(),
-                                                  ns.session_id)) as Box<dyn Session<H>>
-            )
+    fn create_session(&self, caps: Map<String, Value>) -> Result<SessionHandle<H>, WebDriverError> {
+        self.create_default_session(caps)
+            .map(|session| Arc::new(session) as SessionHandle<H>)
     }
 
-    fn get_http_executor(self) -> Box<H> {
-        self.http
+    fn get_http_executor(&self) -> &H {
+        &self.http
     }
 
     fn status(&self) -> Status {
@@ -435,3 +657,9 @@ impl<H: 'static + HttpExecutor> WebDriver<H> for DefaultWebDriver<H> {
         }
     }
 }
+
+impl<H: HttpExecutor> Grid for DefaultWebDriver<H> {
+    fn grid_status(&self) -> Result<GridStatus, WebDriverError> {
+        self.http.get("status")
+    }
+}
@@ -0,0 +1,107 @@
+use serde_json::Map;
+use serde_json::Value;
+
+use wda::structs::WebDriverError;
+use wda::traits::HttpExecutor;
+use wda::traits::Session;
+
+use std::fmt::Debug;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+pub const SCREENSHOT_FILE: &str = "screenshot.png";
+pub const PAGE_SOURCE_FILE: &str = "page.html";
+pub const SUMMARY_FILE: &str = "summary.json";
+
+/// Result of a test function, telling the harness whether artifacts should be captured.
+pub trait TestOutcome {
+    /// Description of the failure, `None` if the test passed.
+    fn failure(&self) -> Option<String>;
+}
+
+impl TestOutcome for () {
+    fn failure(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Errors are described with `Debug`, which for `WebDriverError` keeps the stacktrace and data.
+impl<T, E: Debug> TestOutcome for Result<T, E> {
+    fn failure(&self) -> Option<String> {
+        self.as_ref().err().map(|e| format!("{:?}", e))
+    }
+}
+
+/// Directory of the artifacts of test `name` below `root`, with `::` turned into `.`.
+pub fn test_dir(root: &Path, name: &str) -> PathBuf {
+    let name: String = name.replace("::", ".")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    root.join(name)
+}
+
+/// Saves what the browser shows into `dir` and writes a JSON summary linking the files.
+///
+/// Every piece is captured independently; one that cannot be retrieved is recorded
+/// under `errors` in the summary instead of failing the capture.
+pub fn capture<H: HttpExecutor>(session: &dyn Session<H>, dir: &Path, test: &str, failure: &str) -> io::Result<PathBuf> {
+    if dir.exists() {
+        fs::remove_dir_all(dir)?;
+    }
+    fs::create_dir_all(dir)?;
+    let mut summary = Map::new();
+    let mut errors = Map::new();
+    summary.insert("test".to_string(), Value::String(test.to_string()));
+    summary.insert("session_id".to_string(), Value::String(session.get_session_id().to_string()));
+    summary.insert("capabilities".to_string(), Value::Object(session.get_capabilities().clone()));
+    summary.insert("failure".to_string(), Value::String(failure.to_string()));
+
+    // An open prompt makes every other command fail, so record and dismiss it first.
+    if let Ok(text) = session.get_alert_text() {
+        summary.insert("alert".to_string(), Value::String(text));
+        record(&mut errors, "alert", session.dismiss_alert());
+    }
+
+    if let Some(url) = record(&mut errors, "url", session.get_current_url()) {
+        summary.insert("url".to_string(), Value::String(url));
+    }
+    if let Some(title) = record(&mut errors, "title", session.get_title()) {
+        summary.insert("title".to_string(), Value::String(title));
+    }
+    if let Some(handles) = record(&mut errors, "window_handles", session.get_window_handles()) {
+        summary.insert("window_handles".to_string(), Value::from(handles));
+    }
+    if let Some(cookies) = record(&mut errors, "cookies", session.get_cookies()) {
+        summary.insert("cookies".to_string(), serde_json::to_value(cookies).unwrap_or_default());
+    }
+
+    if let Some(screenshot) = record(&mut errors, "screenshot", session.take_screenshot()) {
+        match base64::decode(screenshot.as_bytes()) {
+            Ok(png) => {
+                fs::write(dir.join(SCREENSHOT_FILE), png)?;
+                summary.insert("screenshot".to_string(), Value::String(SCREENSHOT_FILE.to_string()));
+            }
+            Err(e) => {
+                errors.insert("screenshot".to_string(), Value::String(format!("Invalid base64: {}", e)));
+            }
+        }
+    }
+    if let Some(source) = record(&mut errors, "page_source", session.get_page_source()) {
+        fs::write(dir.join(PAGE_SOURCE_FILE), source)?;
+        summary.insert("page_source".to_string(), Value::String(PAGE_SOURCE_FILE.to_string()));
+    }
+
+    if !errors.is_empty() {
+        summary.insert("errors".to_string(), Value::Object(errors));
+    }
+    let path = dir.join(SUMMARY_FILE);
+    fs::write(&path, serde_json::to_string_pretty(&summary)?)?;
+    Ok(path)
+}
+
+fn record<T>(errors: &mut Map<String, Value>, name: &str, result: Result<T, WebDriverError>) -> Option<T> {
+    result.map_err(|e| errors.insert(name.to_string(), serde_json::to_value(e).unwrap_or_default())).ok()
+}
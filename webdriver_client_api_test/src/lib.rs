@@ -0,0 +1,182 @@
+extern crate base64;
+extern crate log;
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_simple_impl as simple_impl;
+extern crate webdriver_client_api_simplehttp as simplehttp;
+extern crate webdriver_client_api_test_macros;
+
+pub mod artifacts;
+
+pub use webdriver_client_api_test_macros::webdriver_test;
+
+use log::*;
+use serde_json::Map;
+use serde_json::Value;
+
+use simple_impl::DefaultWebDriver;
+use simplehttp::HttpClient;
+use wda::chromium;
+use wda::structs::Capabilities;
+use wda::traits::SessionHandle;
+use wda::traits::WebDriver;
+
+use std::any::Any;
+use std::env;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::path::PathBuf;
+
+use crate::artifacts::TestOutcome;
+
+/// Driver or grid URL, `http://localhost:4444` by default.
+pub const URL_VAR: &str = "WEBDRIVER_URL";
+/// Browser name sent as `browserName`, `firefox` by default.
+pub const BROWSER_VAR: &str = "WEBDRIVER_BROWSER";
+/// Runs the browser headless when set to `1`, `true` or `yes`.
+pub const HEADLESS_VAR: &str = "WEBDRIVER_HEADLESS";
+/// Root of the failure artifacts, `target/webdriver-artifacts` by default.
+pub const ARTIFACTS_VAR: &str = "WEBDRIVER_ARTIFACTS";
+
+/// Session handed to `#[webdriver_test]` functions.
+pub type TestSession = SessionHandle<HttpClient>;
+
+/// Where and how sessions for tests are created.
+#[derive(Clone, Debug)]
+pub struct TestConfig {
+    pub url: String,
+    pub browser: String,
+    pub headless: bool,
+    /// Extra capabilities merged into `alwaysMatch`.
+    pub capabilities: Capabilities,
+    /// Where failing tests leave their artifacts, one directory per test; `None` disables capture.
+    pub artifacts_dir: Option<PathBuf>,
+}
+
+impl TestConfig {
+    pub fn new(url: &str, browser: &str) -> Self {
+        TestConfig {
+            url: url.to_string(),
+            browser: browser.to_string(),
+            headless: false,
+            capabilities: Map::new(),
+            artifacts_dir: None,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let mut config = TestConfig::new(
+            &env::var(URL_VAR).unwrap_or_else(|_| "http://localhost:4444".to_string()),
+            &env::var(BROWSER_VAR).unwrap_or_else(|_| "firefox".to_string()),
+        );
+        config.headless = env::var(HEADLESS_VAR)
+            .map(|v| ["1", "true", "yes"].iter().any(|t| v.eq_ignore_ascii_case(t)))
+            .unwrap_or(false);
+        config.artifacts_dir = Some(env::var_os(ARTIFACTS_VAR)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("target/webdriver-artifacts")));
+        config
+    }
+
+    pub fn for_browser(mut self, browser: &str) -> Self {
+        self.browser = browser.to_string();
+        self
+    }
+
+    pub fn headless(mut self, headless: bool) -> Self {
+        self.headless = headless;
+        self
+    }
+
+    pub fn artifacts_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.artifacts_dir = dir;
+        self
+    }
+
+    pub fn capability(mut self, name: &str, value: Value) -> Self {
+        self.capabilities.insert(name.to_string(), value);
+        self
+    }
+
+    /// New session body with the browser name and, if headless, the vendor specific arguments.
+    /// Browsers without known headless arguments, e.g. Safari, are started as usual.
+    pub fn new_session_body(&self) -> Capabilities {
+        let mut always_match = self.capabilities.clone();
+        always_match.insert("browserName".to_string(), Value::String(self.browser.clone()));
+        let headless = match (self.browser.as_str(), chromium::browser_vendor_prefix(&self.browser)) {
+            _ if !self.headless => None,
+            ("firefox", _) => Some(("moz:firefoxOptions", "-headless")),
+            (_, Some("ms")) => Some(("ms:edgeOptions", "--headless=new")),
+            (_, Some(_)) => Some(("goog:chromeOptions", "--headless=new")),
+            (browser, None) => {
+                warn!("No headless arguments known for {}, ignoring {}", browser, HEADLESS_VAR);
+                None
+            }
+        };
+        if let Some((options, argument)) = headless {
+            let options = always_match.entry(options.to_string())
+                .or_insert_with(|| Value::Object(Map::new()));
+            if let Some(options) = options.as_object_mut() {
+                let args = options.entry("args".to_string()).or_insert_with(|| Value::Array(Vec::new()));
+                if let Some(args) = args.as_array_mut() {
+                    args.push(Value::String(argument.to_string()));
+                }
+            }
+        }
+
+        let mut capabilities = Map::new();
+        capabilities.insert("alwaysMatch".to_string(), Value::Object(always_match));
+        let mut body = Map::new();
+        body.insert("capabilities".to_string(), Value::Object(capabilities));
+        body
+    }
+}
+
+/// Runs `test` with a new session, deleting it afterwards even if `test` panics.
+///
+/// If `test` panics, artifacts are captured before the session is deleted; `name` identifies
+/// the test in logs and artifacts.
+pub fn run<T, F: FnOnce(TestSession) -> T>(name: &str, config: TestConfig, test: F) -> T {
+    run_checked(name, config, test, |_| None)
+}
+
+/// Like `run`, but also captures artifacts if `test` returns an error.
+/// This is what `#[webdriver_test]` expands to.
+pub fn run_with_artifacts<T: TestOutcome, F: FnOnce(TestSession) -> T>(name: &str, config: TestConfig, test: F) -> T {
+    run_checked(name, config, test, T::failure)
+}
+
+fn run_checked<T, F, C>(name: &str, config: TestConfig, test: F, check: C) -> T
+    where F: FnOnce(TestSession) -> T,
+          C: FnOnce(&T) -> Option<String>
+{
+    let driver = DefaultWebDriver::new(Box::new(HttpClient::new(config.url.clone())));
+    let session = driver.create_session(config.new_session_body())
+        .unwrap_or_else(|e| panic!("{}: cannot create a {} session at {}: {}", name, config.browser, config.url, e));
+    debug!("{}: running in session {}", name, session.get_session_id());
+
+    let outcome = panic::catch_unwind(AssertUnwindSafe(|| test(session.clone())));
+
+    let failure = match &outcome {
+        Ok(value) => check(value),
+        Err(payload) => Some(panic_message(payload.as_ref())),
+    };
+    if let (Some(failure), Some(root)) = (failure, &config.artifacts_dir) {
+        let dir = artifacts::test_dir(root, name);
+        match artifacts::capture(session.as_ref(), &dir, name, &failure) {
+            Ok(summary) => info!("{}: failure artifacts written to {}", name, summary.display()),
+            Err(e) => error!("{}: cannot write failure artifacts to {}: {}", name, dir.display(), e),
+        }
+    }
+
+    if let Err(e) = session.delete_session() {
+        warn!("{}: cannot delete session {}: {}", name, session.get_session_id(), e);
+    }
+    outcome.unwrap_or_else(|payload| panic::resume_unwind(payload))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload.downcast_ref::<&str>().map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "test panicked".to_string())
+}
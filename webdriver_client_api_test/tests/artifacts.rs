@@ -0,0 +1,98 @@
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_test as harness;
+
+use harness::artifacts;
+use harness::TestConfig;
+use mock::MockWebDriver;
+use serde_json::json;
+use serde_json::Value;
+use wda::structs::WebDriverError;
+
+use std::env;
+use std::fs;
+use std::panic;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process;
+
+fn artifacts_root(test: &str) -> PathBuf {
+    let root = env::temp_dir().join(format!("webdriver-artifacts-{}-{}", process::id(), test));
+    let _ = fs::remove_dir_all(&root);
+    root
+}
+
+fn summary(root: &Path, test: &str) -> Value {
+    let path = artifacts::test_dir(root, test).join(artifacts::SUMMARY_FILE);
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+#[test]
+fn panicking_tests_leave_artifacts() {
+    let mock = MockWebDriver::start();
+    mock.add_page("https://example.com/", "Example", "<html><body>Hello</body></html>");
+    let root = artifacts_root("panic");
+    let config = TestConfig::new(mock.url(), "firefox").artifacts_dir(Some(root.clone()));
+
+    let outcome = panic::catch_unwind(|| harness::run("suite::checkout", config, |session| {
+        session.navigate_to("https://example.com/").unwrap();
+        panic!("total was 0");
+    }));
+    assert!(outcome.is_err());
+
+    let summary = summary(&root, "suite::checkout");
+    assert_eq!(summary["test"], json!("suite::checkout"));
+    assert_eq!(summary["failure"], json!("total was 0"));
+    assert_eq!(summary["url"], json!("https://example.com/"));
+    assert_eq!(summary["title"], json!("Example"));
+    assert_eq!(summary["window_handles"].as_array().unwrap().len(), 1);
+    assert_eq!(summary["cookies"], json!([]));
+    assert!(summary.get("errors").is_none());
+
+    let dir = artifacts::test_dir(&root, "suite::checkout");
+    assert_eq!(dir.file_name().unwrap(), "suite.checkout");
+    let source = fs::read_to_string(dir.join(summary["page_source"].as_str().unwrap())).unwrap();
+    assert_eq!(source, "<html><body>Hello</body></html>");
+    let png = fs::read(dir.join(summary["screenshot"].as_str().unwrap())).unwrap();
+    assert_eq!(&png[1..4], b"PNG");
+
+    assert_eq!(mock.last_request().unwrap().method, "DELETE");
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn errors_keep_stacktrace_and_data() {
+    let mock = MockWebDriver::start();
+    let root = artifacts_root("error");
+    let config = TestConfig::new(mock.url(), "firefox").artifacts_dir(Some(root.clone()));
+
+    let outcome = harness::run_with_artifacts("returns_error", config, |_| -> Result<(), WebDriverError> {
+        mock.open_alert("Session expired");
+        let mut error = WebDriverError::new("unknown error", "checkout failed");
+        error.stacktrace = "at checkout.js:12".to_string();
+        Err(error)
+    });
+    assert!(outcome.is_err());
+
+    let summary = summary(&root, "returns_error");
+    let failure = summary["failure"].as_str().unwrap();
+    assert!(failure.contains("checkout failed"));
+    assert!(failure.contains("at checkout.js:12"));
+    assert_eq!(summary["alert"], json!("Session expired"));
+    assert_eq!(summary["url"], json!("about:blank"));
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn passing_tests_leave_nothing() {
+    let mock = MockWebDriver::start();
+    let root = artifacts_root("pass");
+    let config = TestConfig::new(mock.url(), "firefox").artifacts_dir(Some(root.clone()));
+
+    harness::run("passes", config, |session| {
+        session.get_title().unwrap();
+    });
+
+    assert!(!root.exists());
+}
@@ -0,0 +1,94 @@
+extern crate serde_json;
+extern crate webdriver_client_api as wda;
+extern crate webdriver_client_api_mock as mock;
+extern crate webdriver_client_api_test as harness;
+
+use harness::webdriver_test;
+use harness::TestConfig;
+use harness::TestSession;
+use mock::MockWebDriver;
+use serde_json::json;
+use wda::structs::WebDriverError;
+
+use std::panic;
+use std::sync::OnceLock;
+
+static MOCK: OnceLock<MockWebDriver> = OnceLock::new();
+
+fn mock_config() -> TestConfig {
+    let mock = MOCK.get_or_init(|| {
+        let mock = MockWebDriver::start();
+        mock.add_page("https://example.com/", "Example", "<html></html>");
+        mock
+    });
+    TestConfig::new(mock.url(), "firefox")
+}
+
+#[webdriver_test(config = mock_config)]
+fn session_is_injected(session: TestSession) {
+    session.navigate_to("https://example.com/").unwrap();
+    assert_eq!(session.get_title().unwrap(), "Example");
+}
+
+#[webdriver_test(config = mock_config)]
+fn tests_may_return_results(session: TestSession) -> Result<(), WebDriverError> {
+    session.navigate_to("https://example.com/")?;
+    assert_eq!(session.get_current_url()?, "https://example.com/");
+    Ok(())
+}
+
+#[webdriver_test(config = mock_config)]
+#[should_panic(expected = "failing on purpose")]
+fn panics_propagate(_session: TestSession) {
+    panic!("failing on purpose");
+}
+
+#[webdriver_test(config = mock_config, browsers = ["chrome", "MicrosoftEdge"])]
+fn runs_per_browser(session: TestSession) {
+    let browser = session.get_capabilities()["browserName"].as_str().unwrap().to_string();
+    assert!(browser == "chrome" || browser == "MicrosoftEdge");
+}
+
+#[test]
+fn browser_tests_are_grouped_in_a_module() {
+    runs_per_browser::chrome();
+    runs_per_browser::microsoftedge();
+}
+
+#[test]
+fn session_is_deleted_when_the_test_panics() {
+    let mock = MockWebDriver::start();
+    let config = TestConfig::new(mock.url(), "firefox");
+
+    let outcome = panic::catch_unwind(|| harness::run("panicking", config, |_| panic!("boom")));
+
+    assert!(outcome.is_err());
+    assert_eq!(mock.requests().last().unwrap().method, "DELETE");
+    assert!(mock.state().sessions.is_empty());
+}
+
+#[test]
+fn headless_adds_vendor_arguments() {
+    let config = TestConfig::new("http://localhost:4444", "chrome")
+        .headless(true)
+        .capability("goog:chromeOptions", json!({"args": ["--window-size=800,600"]}));
+
+    assert_eq!(config.new_session_body()["capabilities"]["alwaysMatch"], json!({
+        "browserName": "chrome",
+        "goog:chromeOptions": {"args": ["--window-size=800,600", "--headless=new"]},
+    }));
+    assert_eq!(
+        config.for_browser("firefox").new_session_body()["capabilities"]["alwaysMatch"]["moz:firefoxOptions"],
+        json!({"args": ["-headless"]}),
+    );
+}
+
+#[test]
+fn headless_is_left_to_unknown_browsers() {
+    let config = TestConfig::new("http://localhost:4444", "safari").headless(true);
+    assert_eq!(config.new_session_body()["capabilities"]["alwaysMatch"], json!({"browserName": "safari"}));
+
+    let edge = config.for_browser("MicrosoftEdge").new_session_body();
+    assert_eq!(edge["capabilities"]["alwaysMatch"]["ms:edgeOptions"], json!({"args": ["--headless=new"]}));
+    assert!(edge["capabilities"]["alwaysMatch"].get("goog:chromeOptions").is_none());
+}
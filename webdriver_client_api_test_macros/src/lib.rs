@@ -0,0 +1,132 @@
+extern crate proc_macro;
+extern crate proc_macro2;
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::parse_macro_input;
+use syn::Error;
+use syn::Expr;
+use syn::ExprArray;
+use syn::ExprLit;
+use syn::Ident;
+use syn::ItemFn;
+use syn::Lit;
+use syn::LitStr;
+use syn::Path;
+
+#[derive(Default)]
+struct Args {
+    browsers: Vec<LitStr>,
+    config: Option<Path>,
+}
+
+/// Turns `fn name(session: TestSession)` into a test running against a fresh session.
+///
+/// The session is configured by `TestConfig::from_env` unless `config = path::to::fn` names
+/// another `fn() -> TestConfig`, and is deleted once the test returns or panics.
+/// With `browsers = ["chrome", "firefox"]` a module `name` holds one test per browser.
+#[proc_macro_attribute]
+pub fn webdriver_test(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut parsed = Args::default();
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("browsers") {
+            let browsers: ExprArray = meta.value()?.parse()?;
+            for browser in browsers.elems {
+                match browser {
+                    Expr::Lit(ExprLit { lit: Lit::Str(name), .. }) => parsed.browsers.push(name),
+                    other => return Err(Error::new_spanned(other, "expected a browser name")),
+                }
+            }
+            Ok(())
+        } else if meta.path.is_ident("config") {
+            parsed.config = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported webdriver_test argument, expected `browsers` or `config`"))
+        }
+    });
+    parse_macro_input!(args with parser);
+    let function = parse_macro_input!(item as ItemFn);
+
+    expand(parsed, function)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(args: Args, mut function: ItemFn) -> Result<TokenStream2, Error> {
+    let sig = &function.sig;
+    if sig.asyncness.is_some() {
+        return Err(Error::new_spanned(sig.asyncness, "webdriver tests cannot be async"));
+    }
+    if !sig.generics.params.is_empty() {
+        return Err(Error::new_spanned(&sig.generics, "webdriver tests cannot be generic"));
+    }
+    if sig.inputs.len() != 1 {
+        return Err(Error::new_spanned(&sig.inputs, "webdriver tests take exactly one session argument"));
+    }
+
+    let attrs = std::mem::take(&mut function.attrs);
+    let vis = function.vis.clone();
+    let name = function.sig.ident.clone();
+    let output = function.sig.output.clone();
+    let config = match args.config {
+        Some(path) => quote!(#path()),
+        None => quote!(::webdriver_client_api_test::TestConfig::from_env()),
+    };
+
+    if args.browsers.is_empty() {
+        return Ok(quote! {
+            #(#attrs)*
+            #[test]
+            #vis fn #name() #output {
+                #function
+                ::webdriver_client_api_test::run_with_artifacts(
+                    concat!(module_path!(), "::", stringify!(#name)),
+                    #config,
+                    #name,
+                )
+            }
+        });
+    }
+
+    let tests = args.browsers.iter().map(|browser| {
+        let test = browser_ident(browser);
+        quote! {
+            #(#attrs)*
+            #[test]
+            pub fn #test() #output {
+                ::webdriver_client_api_test::run_with_artifacts(
+                    concat!(module_path!(), "::", stringify!(#test)),
+                    #config.for_browser(#browser),
+                    #name,
+                )
+            }
+        }
+    });
+    Ok(quote! {
+        #vis mod #name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #function
+
+            #(#tests)*
+        }
+    })
+}
+
+/// `MicrosoftEdge` becomes `microsoftedge`, `chrome-beta` becomes `chrome_beta`.
+fn browser_ident(browser: &LitStr) -> Ident {
+    let mut name: String = browser.value()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    Ident::new(&name, Span::call_site())
+}